@@ -1,6 +1,12 @@
 extern crate cc;
 
+use std::env;
+
 fn main() {
+    // `soft-float` replaces `src/f128.c` with Rust, leaving nothing to compile or link.
+    if env::var_os("CARGO_FEATURE_SOFT_FLOAT").is_some() {
+        return;
+    }
     //gcc::Config::new().file("src/f80.c").flag("-lmath").compile("libf80.a");("libf80.a", &["src/f80.c"]);
     cc::Build::new()
         .flag("-Bstatic")
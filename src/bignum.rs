@@ -0,0 +1,249 @@
+//! Arbitrary-precision unsigned integers, with just the operations exact parsing and formatting
+//! need.
+
+use std::cmp::Ordering;
+
+/// An unsigned integer stored as little-endian 32-bit limbs without trailing zero limbs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Big {
+    limbs: Vec<u32>,
+}
+
+impl Big {
+    pub fn zero() -> Big {
+        Big { limbs: Vec::new() }
+    }
+
+    pub fn from_u128(mut x: u128) -> Big {
+        let mut limbs = Vec::with_capacity(4);
+        while x != 0 {
+            limbs.push(x as u32);
+            x >>= 32;
+        }
+        Big { limbs }
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// The number of significant bits.
+    pub fn bit_len(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(&top) => 32 * self.limbs.len() as u64 - top.leading_zeros() as u64,
+        }
+    }
+
+    #[inline]
+    pub fn bit(&self, i: u64) -> bool {
+        let limb = (i / 32) as usize;
+        limb < self.limbs.len() && self.limbs[limb] >> (i % 32) & 1 != 0
+    }
+
+    /// Whether any of the bits below bit `n` is set.
+    pub fn any_below(&self, n: u64) -> bool {
+        let full = ((n / 32) as usize).min(self.limbs.len());
+        if self.limbs[..full].iter().any(|&l| l != 0) {
+            return true;
+        }
+        full < self.limbs.len() && n % 32 != 0 && self.limbs[full] << (32 - n % 32) != 0
+    }
+
+    /// `self * m + a`.
+    pub fn mul_add_small(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        for limb in &mut self.limbs {
+            let t = *limb as u64 * m as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    /// Multiplies by `base^n`.
+    pub fn mul_pow(&mut self, base: u32, mut n: u64) {
+        // Multiply by the largest power of `base` that fits in a limb as often as possible.
+        let (mut chunk, mut chunk_exp) = (base, 1);
+        while let Some(next) = chunk.checked_mul(base) {
+            chunk = next;
+            chunk_exp += 1;
+        }
+        while n >= chunk_exp {
+            self.mul_add_small(chunk, 0);
+            n -= chunk_exp;
+        }
+        if n > 0 {
+            self.mul_add_small(base.pow(n as u32), 0);
+        }
+    }
+
+    pub fn shl(&mut self, bits: u64) {
+        if self.is_zero() || bits == 0 {
+            return;
+        }
+        let (limbs, bits) = ((bits / 32) as usize, (bits % 32) as u32);
+        if bits != 0 {
+            let mut carry = 0;
+            for limb in &mut self.limbs {
+                let t = *limb;
+                *limb = t << bits | carry;
+                carry = t >> (32 - bits);
+            }
+            if carry != 0 {
+                self.limbs.push(carry);
+            }
+        }
+        self.limbs.splice(0..0, ::std::iter::repeat(0).take(limbs));
+    }
+
+    pub fn shr(&mut self, bits: u64) {
+        let limbs = ((bits / 32) as usize).min(self.limbs.len());
+        self.limbs.drain(..limbs);
+        let bits = (bits % 32) as u32;
+        if bits != 0 {
+            let mut carry = 0;
+            for limb in self.limbs.iter_mut().rev() {
+                let t = *limb;
+                *limb = t >> bits | carry;
+                carry = t << (32 - bits);
+            }
+        }
+        self.trim();
+    }
+
+    pub fn add_assign(&mut self, other: &Big) {
+        if self.limbs.len() < other.limbs.len() {
+            self.limbs.resize(other.limbs.len(), 0);
+        }
+        let mut carry = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let t = *limb as u64 + other.limbs.get(i).cloned().unwrap_or(0) as u64 + carry;
+            *limb = t as u32;
+            carry = t >> 32;
+        }
+        if carry != 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    /// `self -= other`, which must not be larger than `self`.
+    pub fn sub_assign(&mut self, other: &Big) {
+        let mut borrow = 0;
+        for (i, limb) in self.limbs.iter_mut().enumerate() {
+            let o = other.limbs.get(i).cloned().unwrap_or(0) as u64;
+            let t = (*limb as u64).wrapping_sub(o + borrow);
+            *limb = t as u32;
+            borrow = t >> 63;
+        }
+        debug_assert_eq!(borrow, 0);
+        self.trim();
+    }
+
+    /// Divides by `d` in place and returns the remainder.
+    pub fn div_rem_small(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        for limb in self.limbs.iter_mut().rev() {
+            let t = rem << 32 | *limb as u64;
+            *limb = (t / d as u64) as u32;
+            rem = t % d as u64;
+        }
+        self.trim();
+        rem as u32
+    }
+
+    /// The decimal digits of `self` as ASCII, most significant first; empty for zero.
+    pub fn to_decimal(mut self) -> Vec<u8> {
+        // Peel off nine digits at a time, least significant first.
+        let mut chunks = Vec::with_capacity(self.limbs.len() * 32 / 29 + 1);
+        while !self.is_zero() {
+            chunks.push(self.div_rem_small(1_000_000_000));
+        }
+        let mut digits = Vec::with_capacity(9 * chunks.len());
+        for (i, &chunk) in chunks.iter().rev().enumerate() {
+            let mut buf = [b'0'; 9];
+            let mut c = chunk;
+            for d in buf.iter_mut().rev() {
+                *d = b'0' + (c % 10) as u8;
+                c /= 10;
+            }
+            // The leading chunk is not zero, and is written without its leading zeros.
+            let skip = if i == 0 {
+                buf.iter().position(|&d| d != b'0').unwrap()
+            } else {
+                0
+            };
+            digits.extend_from_slice(&buf[skip..]);
+        }
+        digits
+    }
+
+    /// The bits of `self` from bit `n` up, which must fit in a `u128`.
+    pub fn bits_from(&self, n: u64) -> u128 {
+        let mut shifted = self.clone();
+        shifted.shr(n);
+        debug_assert!(shifted.limbs.len() <= 4);
+        shifted
+            .limbs
+            .iter()
+            .rev()
+            .fold(0, |acc, &l| acc << 32 | l as u128)
+    }
+
+    /// `floor(self / d)` and whether the division left a remainder. The quotient must fit in a
+    /// `u128`.
+    pub fn div_rem_small_quotient(&self, d: &Big) -> (u128, bool) {
+        let (n_len, d_len) = (self.bit_len(), d.bit_len());
+        if n_len < d_len {
+            return (0, !self.is_zero());
+        }
+        // The remainder starts out as the top `d_len - 1` bits, which are smaller than `d`; every
+        // further bit of `self` then adds one bit to the quotient.
+        let steps = n_len - d_len + 1;
+        debug_assert!(steps <= 128);
+        let mut rem = self.clone();
+        rem.shr(steps);
+        let mut q = 0u128;
+        for i in (0..steps).rev() {
+            rem.shl(1);
+            if self.bit(i) {
+                if rem.is_zero() {
+                    rem.limbs.push(1);
+                } else {
+                    rem.limbs[0] |= 1;
+                }
+            }
+            q <<= 1;
+            if rem.cmp(d) != Ordering::Less {
+                rem.sub_assign(d);
+                q |= 1;
+            }
+        }
+        (q, !rem.is_zero())
+    }
+
+    fn trim(&mut self) {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+    }
+}
+
+impl PartialOrd for Big {
+    fn partial_cmp(&self, other: &Big) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Big {
+    fn cmp(&self, other: &Big) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
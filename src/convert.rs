@@ -0,0 +1,275 @@
+//! Conversions between `f128` and the primitive integers. Those from `f128` are computed from the
+//! bits rather than by C casts, which are undefined for NaN and out-of-range values.
+
+use f128_t::f128;
+use flags::{self, FpFlags};
+use num_traits::{AsPrimitive, Float, NumCast};
+use rounding::{self, RoundingMode};
+use soft;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+/// Why an `f128` does not convert to an integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TryFromF128Error {
+    /// The value is NaN.
+    Nan,
+    /// The value is larger than the largest value of the integer type, or positive infinity.
+    Overflow,
+    /// The value is smaller than the smallest value of the integer type, or negative infinity.
+    Underflow,
+    /// The value lies in range but has a fractional part.
+    Inexact,
+}
+
+impl fmt::Display for TryFromF128Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            TryFromF128Error::Nan => "cannot convert NaN to an integer",
+            TryFromF128Error::Overflow => "f128 too large to fit in the integer type",
+            TryFromF128Error::Underflow => "f128 too small to fit in the integer type",
+            TryFromF128Error::Inexact => "f128 has a fractional part",
+        })
+    }
+}
+
+impl Error for TryFromF128Error {}
+
+/// `x` rounded toward zero as a sign and a magnitude of at most `max` for positive and
+/// `min_magnitude` for negative values, and whether rounding dropped a fractional part.
+fn truncate(
+    x: f128,
+    max: u128,
+    min_magnitude: u128,
+) -> Result<(bool, u128, bool), TryFromF128Error> {
+    if x.is_nan() {
+        return Err(TryFromF128Error::Nan);
+    }
+    let (negative, exp, sig) = x.decode();
+    let out_of_range = if negative {
+        TryFromF128Error::Underflow
+    } else {
+        TryFromF128Error::Overflow
+    };
+    let (magnitude, inexact) = if sig == 0 {
+        (0, false)
+    } else if exp >= 0 {
+        // Infinities have a large exponent too.
+        if exp as u32 + (128 - sig.leading_zeros()) > 128 {
+            return Err(out_of_range);
+        }
+        (sig << exp, false)
+    } else if exp > -128 {
+        (sig >> -exp, sig & ((1 << -exp) - 1) != 0)
+    } else {
+        (0, true)
+    };
+    if magnitude > if negative { min_magnitude } else { max } {
+        return Err(out_of_range);
+    }
+    Ok((negative, magnitude, inexact))
+}
+
+/// The error returned when an integer has no exact `f128` representation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InexactError {
+    nearest: f128,
+}
+
+impl InexactError {
+    /// The `f128` nearest to the integer, ties to even.
+    pub fn nearest(&self) -> f128 {
+        self.nearest
+    }
+}
+
+impl fmt::Display for InexactError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("integer has no exact f128 representation")
+    }
+}
+
+impl Error for InexactError {}
+
+/// The primitive integers, as targets of conversions from `f128`.
+pub(crate) trait Int: Sized {
+    /// `x` rounded toward zero, and whether that dropped a fractional part.
+    fn truncate(x: f128) -> Result<(Self, bool), TryFromF128Error>;
+}
+
+/// `x` rounded toward zero, or `None` if that is NaN or out of range for `T`, which raises the
+/// invalid exception.
+pub(crate) fn truncated<T: Int>(x: f128) -> Option<T> {
+    checked_truncate::<T>(x).ok().map(|(n, _)| n)
+}
+
+/// `T::truncate`, raising the invalid exception if it fails.
+fn checked_truncate<T: Int>(x: f128) -> Result<(T, bool), TryFromF128Error> {
+    let result = T::truncate(x);
+    if result.is_err() {
+        flags::raise(FpFlags::INVALID);
+    }
+    result
+}
+
+macro_rules! impl_int {
+    ($($ty:ident)*) => ($(
+        impl Int for $ty {
+            fn truncate(x: f128) -> Result<($ty, bool), TryFromF128Error> {
+                let min_magnitude = ($ty::min_value() as i128).wrapping_neg() as u128;
+                let (negative, magnitude, inexact) =
+                    truncate(x, $ty::max_value() as u128, min_magnitude)?;
+                let n = if negative {
+                    (magnitude as $ty).wrapping_neg()
+                } else {
+                    magnitude as $ty
+                };
+                Ok((n, inexact))
+            }
+        }
+
+        /// Converts exactly: NaN, values out of range and values with a fractional part are
+        /// errors. The first two raise the invalid exception.
+        impl TryFrom<f128> for $ty {
+            type Error = TryFromF128Error;
+
+            fn try_from(x: f128) -> Result<$ty, TryFromF128Error> {
+                match checked_truncate::<$ty>(x)? {
+                    (_, true) => Err(TryFromF128Error::Inexact),
+                    (n, false) => Ok(n),
+                }
+            }
+        }
+
+        /// Rounds toward zero like `as` does for the primitive floats: NaN becomes 0, and values
+        /// out of range saturate to the smallest or largest integer.
+        impl AsPrimitive<$ty> for f128 {
+            #[inline]
+            fn as_(self) -> $ty {
+                match <$ty as Int>::truncate(self) {
+                    Ok((n, _)) => n,
+                    Err(TryFromF128Error::Overflow) => $ty::max_value(),
+                    Err(TryFromF128Error::Underflow) => $ty::min_value(),
+                    Err(_) => 0,
+                }
+            }
+        }
+    )*)
+}
+
+impl_int! { i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize }
+
+/// The primitive integers, as sources of conversions to `f128` with a choice of rounding.
+///
+/// Integers of up to 113 bits always convert exactly; wider ones are rounded.
+pub trait IntoF128: Copy {
+    /// `self` rounded to an `f128` in `mode`, and how the result compares with `self`: `Less`
+    /// if it was rounded down, `Greater` if it was rounded up and `Equal` if it is exact.
+    fn into_f128_with_rounding(self, mode: RoundingMode) -> (f128, Ordering);
+}
+
+impl IntoF128 for u128 {
+    fn into_f128_with_rounding(self, mode: RoundingMode) -> (f128, Ordering) {
+        let x = soft::from_u128(self, mode);
+        // Only 2^128 itself does not fit back into a `u128`.
+        let direction = match <u128 as Int>::truncate(x) {
+            Ok((n, _)) => n.cmp(&self),
+            Err(_) => Ordering::Greater,
+        };
+        (x, direction)
+    }
+}
+
+impl IntoF128 for i128 {
+    fn into_f128_with_rounding(self, mode: RoundingMode) -> (f128, Ordering) {
+        let x = soft::from_i128(self, mode);
+        // Only 2^127 itself does not fit back into an `i128`.
+        let direction = match <i128 as Int>::truncate(x) {
+            Ok((n, _)) => n.cmp(&self),
+            Err(_) => Ordering::Greater,
+        };
+        (x, direction)
+    }
+}
+
+macro_rules! impl_into_f128 {
+    ($wide:ty => $($ty:ty)*) => ($(
+        impl IntoF128 for $ty {
+            fn into_f128_with_rounding(self, mode: RoundingMode) -> (f128, Ordering) {
+                (self as $wide).into_f128_with_rounding(mode)
+            }
+        }
+    )*)
+}
+
+impl_into_f128! { u128 => u8 u16 u32 u64 usize }
+impl_into_f128! { i128 => i8 i16 i32 i64 isize }
+
+/// Rounds in the current `RoundingMode`.
+impl AsPrimitive<f32> for f128 {
+    #[inline]
+    fn as_(self) -> f32 {
+        rounding::f128_to_f32(self)
+    }
+}
+
+/// Rounds in the current `RoundingMode`.
+impl AsPrimitive<f64> for f128 {
+    #[inline]
+    fn as_(self) -> f64 {
+        rounding::f128_to_f64(self)
+    }
+}
+
+impl AsPrimitive<f128> for f128 {
+    #[inline]
+    fn as_(self) -> f128 {
+        self
+    }
+}
+
+impl f128 {
+    /// Converts to `T` with the semantics of `as` on the primitive floats: integers are rounded
+    /// toward zero, NaN becomes 0 and values out of range saturate to `T`'s smallest or largest
+    /// value, so that this never fails.
+    ///
+    /// For a conversion that reports those cases instead, use `ToPrimitive` or `T::try_from`.
+    #[inline]
+    pub fn saturating_to<T: 'static + Copy>(self) -> T
+    where
+        f128: AsPrimitive<T>,
+    {
+        self.as_()
+    }
+
+    /// Converts `n` if that needs no rounding, which integers of up to 113 bits never do. Otherwise
+    /// the error holds the nearest `f128`.
+    pub fn try_from_exact<T: IntoF128>(n: T) -> Result<f128, InexactError> {
+        match n.into_f128_with_rounding(RoundingMode::NearestEven) {
+            (x, Ordering::Equal) => Ok(x),
+            (nearest, _) => Err(InexactError { nearest }),
+        }
+    }
+
+    /// Converts to any `NumCast` type through the `ToPrimitive` conversion that fits it best:
+    /// integers up to 128 bits wide exactly, after rounding toward zero, and `f32` and `f64`
+    /// rounded in the current rounding mode. `None` for NaN and for values out of range of an
+    /// integer type.
+    #[inline]
+    pub fn cast<T: NumCast>(self) -> Option<T> {
+        T::from(self)
+    }
+
+    /// `n` rounded in `mode` regardless of the current rounding mode, and how the result compares
+    /// with `n`: `Less` if it was rounded down, `Greater` if up and `Equal` if it is exact.
+    pub fn from_u128_with_rounding(n: u128, mode: RoundingMode) -> (f128, Ordering) {
+        n.into_f128_with_rounding(mode)
+    }
+
+    /// Like `from_u128_with_rounding`, for signed integers.
+    pub fn from_i128_with_rounding(n: i128, mode: RoundingMode) -> (f128, Ordering) {
+        n.into_f128_with_rounding(mode)
+    }
+}
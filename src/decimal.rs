@@ -0,0 +1,421 @@
+//! Exact and shortest decimal formatting of `f128` for `Display`, `Debug`, `LowerExp` and
+//! `UpperExp`.
+
+use bignum::Big;
+use f128_t::f128;
+use num_traits::Float;
+use std::cmp::Ordering;
+use std::fmt::{self, Write};
+use std::str;
+
+/// Precisions beyond this only add zeros: every `f128` is exact with 16494 fraction digits.
+const PRECISION_LIMIT: usize = 20_000;
+
+/// The smallest exponent `f128::decode` returns, that of the subnormals.
+const MIN_EXP: i32 = -16494;
+
+/// The non-negative number `d.ddd * 10^exp`, with the digits in ASCII and no trailing zeros. Zero
+/// has no digits.
+struct Decimal {
+    digits: Vec<u8>,
+    exp: isize,
+}
+
+impl Decimal {
+    /// The exact decimal expansion of `sig * 2^exp`.
+    fn from_parts(sig: u128, exp: i32) -> Decimal {
+        if sig == 0 {
+            return Decimal {
+                digits: Vec::new(),
+                exp: 0,
+            };
+        }
+        // `sig * 2^exp` is `n * 10^-frac` for an integer `n`.
+        let mut n = Big::from_u128(sig);
+        let frac = if exp >= 0 {
+            n.shl(exp as u64);
+            0
+        } else {
+            n.mul_pow(5, -exp as u64);
+            -exp as isize
+        };
+        let digits = n.to_decimal();
+        let mut d = Decimal {
+            exp: digits.len() as isize - 1 - frac,
+            digits,
+        };
+        d.trim();
+        d
+    }
+
+    /// The exact decimal expansion of the magnitude of the finite `x`.
+    fn exact(x: f128) -> Decimal {
+        let (_, exp, sig) = x.decode();
+        Decimal::from_parts(sig, exp)
+    }
+
+    /// The decimal with the fewest significant digits that rounds back to the finite `x`, and of
+    /// those the one nearest to `x`.
+    fn shortest(x: f128) -> Decimal {
+        let (_, exp, sig) = x.decode();
+        if sig == 0 {
+            return Decimal::from_parts(0, 0);
+        }
+        // This is Steele and White's free-format algorithm as refined by Burger and Dybvig: `x`
+        // is `r / s`, and the midpoints to its neighbours lie `m_minus / s` below and
+        // `m_plus / s` above it. Below a power of two the gap is half as wide.
+        let narrow = sig == 1 << 112 && exp > MIN_EXP;
+        let shift = if narrow { 2 } else { 1 };
+        let mut r = Big::from_u128(sig << shift);
+        let mut s = Big::from_u128(1 << shift);
+        let mut m_plus = Big::from_u128(shift as u128);
+        let mut m_minus = Big::from_u128(1);
+        if exp >= 0 {
+            r.shl(exp as u64);
+            m_plus.shl(exp as u64);
+            m_minus.shl(exp as u64);
+        } else {
+            s.shl(-exp as u64);
+        }
+
+        // Scale by a power of ten so that `x / 10^k` lies in `[0.1, 1)`, starting from an
+        // estimate of `k` that is exact or one too small.
+        let log10 = (sig as f64).log10() + exp as f64 * ::std::f64::consts::LOG10_2;
+        let mut k = (log10 - 1e-6).ceil() as isize;
+        if k >= 0 {
+            s.mul_pow(10, k as u64);
+        } else {
+            r.mul_pow(10, -k as u64);
+            m_plus.mul_pow(10, -k as u64);
+            m_minus.mul_pow(10, -k as u64);
+        }
+        // The midpoints are included when ties round to `x`.
+        let inclusive = sig & 1 == 0;
+        let reaches =
+            |order: Ordering| order == Ordering::Less || inclusive && order == Ordering::Equal;
+        let high_reaches = |r: &Big, m_plus: &Big, s: &Big| {
+            let mut high = r.clone();
+            high.add_assign(m_plus);
+            reaches(s.cmp(&high))
+        };
+        if high_reaches(&r, &m_plus, &s) {
+            s.mul_add_small(10, 0);
+            k += 1;
+        }
+
+        // Generate digits until stopping after one of them rounds back to `x`.
+        let mut d = Decimal {
+            digits: Vec::with_capacity(36),
+            exp: k - 1,
+        };
+        loop {
+            r.mul_add_small(10, 0);
+            m_plus.mul_add_small(10, 0);
+            m_minus.mul_add_small(10, 0);
+            let mut digit = 0;
+            while r >= s {
+                r.sub_assign(&s);
+                digit += 1;
+            }
+            d.digits.push(b'0' + digit);
+            let low = reaches(r.cmp(&m_minus));
+            let high = high_reaches(&r, &m_plus, &s);
+            if low || high {
+                // When both the digit and the next one up are close enough, take the nearer.
+                let up = if low && high {
+                    let mut twice = r.clone();
+                    twice.shl(1);
+                    match twice.cmp(&s) {
+                        Ordering::Less => false,
+                        Ordering::Equal => digit & 1 != 0,
+                        Ordering::Greater => true,
+                    }
+                } else {
+                    high
+                };
+                if up {
+                    let n = d.digits.len();
+                    d.increment(n);
+                }
+                d.trim();
+                return d;
+            }
+        }
+    }
+
+    /// Rounds to `n` significant digits, to nearest with ties to even. `n` may be zero or
+    /// negative, in which case the number becomes zero or a single carry digit.
+    fn round(&mut self, n: isize) {
+        if n >= self.digits.len() as isize {
+            return;
+        }
+        if n < 0 {
+            self.digits.clear();
+            return;
+        }
+        let keep = n as usize;
+        let first = self.digits[keep];
+        // Nothing but zeros follows the last digit, so any digit after `first` breaks a tie.
+        let up = first > b'5'
+            || first == b'5'
+                && (self.digits.len() > keep + 1 || keep > 0 && self.digits[keep - 1] & 1 != 0);
+        self.truncate(keep);
+        if up {
+            self.increment(keep);
+        }
+    }
+
+    /// Drops all but the first `n` significant digits.
+    fn truncate(&mut self, n: usize) {
+        self.digits.truncate(n);
+        self.trim();
+    }
+
+    /// Adds one unit in the `n`th significant digit.
+    fn increment(&mut self, n: usize) {
+        self.digits.resize(n, b'0');
+        while self.digits.last() == Some(&b'9') {
+            self.digits.pop();
+        }
+        match self.digits.last_mut() {
+            Some(d) => *d += 1,
+            None => {
+                self.digits.push(b'1');
+                self.exp += 1;
+            }
+        }
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.digits.last() == Some(&b'0') {
+            self.digits.pop();
+        }
+    }
+
+    /// How many fraction digits it takes to write the number in full.
+    fn frac_digits(&self) -> usize {
+        (self.digits.len() as isize - 1 - self.exp).max(0) as usize
+    }
+
+    /// The digits with indices in `from..to`, clamped to the ones there are.
+    fn slice(&self, from: usize, to: usize) -> &[u8] {
+        let len = self.digits.len();
+        &self.digits[from.min(len)..to.min(len)]
+    }
+}
+
+/// A piece of formatted output.
+#[derive(Clone, Copy)]
+enum Part<'a> {
+    Str(&'a str),
+    Digits(&'a [u8]),
+    Zeros(usize),
+    Exp(isize),
+}
+
+impl<'a> Part<'a> {
+    fn len(&self) -> usize {
+        match *self {
+            Part::Str(s) => s.len(),
+            Part::Digits(d) => d.len(),
+            Part::Zeros(n) => n,
+            Part::Exp(e) => {
+                let mut len = 1 + (e < 0) as usize;
+                let mut e = e.abs();
+                while e >= 10 {
+                    e /= 10;
+                    len += 1;
+                }
+                len
+            }
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Part::Str(s) => f.write_str(s),
+            Part::Digits(d) => f.write_str(str::from_utf8(d).unwrap()),
+            Part::Zeros(n) => write_repeated(f, '0', n),
+            Part::Exp(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+fn write_repeated(f: &mut fmt::Formatter, c: char, n: usize) -> fmt::Result {
+    for _ in 0..n {
+        f.write_char(c)?;
+    }
+    Ok(())
+}
+
+/// Writes `sign` and `parts` padded to the formatter's width the way numbers are: aligned right
+/// unless asked otherwise, or with zeros after the sign for the `0` flag.
+fn pad(f: &mut fmt::Formatter, sign: &str, parts: &[Part]) -> fmt::Result {
+    let len = sign.len() + parts.iter().map(Part::len).sum::<usize>();
+    let padding = f.width().unwrap_or(0).saturating_sub(len);
+    let (before, after) = if padding == 0 || f.sign_aware_zero_pad() {
+        (0, 0)
+    } else {
+        match f.align() {
+            Some(fmt::Alignment::Left) => (0, padding),
+            Some(fmt::Alignment::Center) => (padding / 2, (padding + 1) / 2),
+            _ => (padding, 0),
+        }
+    };
+    let fill = f.fill();
+    write_repeated(f, fill, before)?;
+    f.write_str(sign)?;
+    if f.sign_aware_zero_pad() {
+        write_repeated(f, '0', padding)?;
+    }
+    for part in parts {
+        part.write(f)?;
+    }
+    write_repeated(f, fill, after)
+}
+
+/// Writes `d` in positional notation with `frac` fraction digits, which must include all of
+/// its digits.
+fn positional(f: &mut fmt::Formatter, sign: &str, d: &Decimal, frac: usize) -> fmt::Result {
+    let int_digits = (d.exp + 1).max(0) as usize;
+    let lead_zeros = ((-1 - d.exp).max(0) as usize).min(frac);
+    let frac_digits = d.slice(int_digits, int_digits.saturating_add(frac - lead_zeros));
+    let mut parts = [Part::Str("0"); 6];
+    let mut n = 1;
+    if int_digits > 0 && !d.digits.is_empty() {
+        let digits = d.slice(0, int_digits);
+        parts[0] = Part::Digits(digits);
+        parts[1] = Part::Zeros(int_digits - digits.len());
+        n = 2;
+    }
+    if frac > 0 {
+        parts[n] = Part::Str(".");
+        parts[n + 1] = Part::Zeros(lead_zeros);
+        parts[n + 2] = Part::Digits(frac_digits);
+        parts[n + 3] = Part::Zeros(frac - lead_zeros - frac_digits.len());
+        n += 4;
+    }
+    pad(f, sign, &parts[..n])
+}
+
+/// Writes `d` as `d.ddde±x` with `frac` digits after the point, which must include all of its
+/// digits, and `marker` before the exponent.
+fn scientific(
+    f: &mut fmt::Formatter,
+    sign: &str,
+    d: &Decimal,
+    frac: usize,
+    marker: &str,
+) -> fmt::Result {
+    let lead = if d.digits.is_empty() {
+        Part::Str("0")
+    } else {
+        Part::Digits(d.slice(0, 1))
+    };
+    let frac_digits = d.slice(1, frac.saturating_add(1));
+    if frac > 0 {
+        pad(
+            f,
+            sign,
+            &[
+                lead,
+                Part::Str("."),
+                Part::Digits(frac_digits),
+                Part::Zeros(frac - frac_digits.len()),
+                Part::Str(marker),
+                Part::Exp(d.exp),
+            ],
+        )
+    } else {
+        pad(f, sign, &[lead, Part::Str(marker), Part::Exp(d.exp)])
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Notation {
+    Positional,
+    Scientific(&'static str),
+    /// Positional with at least one fraction digit for moderate magnitudes, scientific for the
+    /// others.
+    Debug,
+}
+
+fn fmt_decimal(x: f128, f: &mut fmt::Formatter, notation: Notation) -> fmt::Result {
+    if x.is_nan() {
+        return pad(f, "", &[Part::Str("NaN")]);
+    }
+    let sign = if x.is_sign_negative() {
+        "-"
+    } else if f.sign_plus() {
+        "+"
+    } else {
+        ""
+    };
+    if x.is_infinite() {
+        return pad(f, sign, &[Part::Str("inf")]);
+    }
+
+    match (notation, f.precision()) {
+        (Notation::Scientific(marker), Some(p)) => {
+            let mut d = Decimal::exact(x);
+            d.round(p.min(PRECISION_LIMIT) as isize + 1);
+            scientific(f, sign, &d, p, marker)
+        }
+        (Notation::Scientific(marker), None) => {
+            let d = Decimal::shortest(x);
+            scientific(f, sign, &d, d.digits.len().max(1) - 1, marker)
+        }
+        (_, Some(p)) => {
+            let mut d = Decimal::exact(x);
+            let n = d.exp + 1 + p.min(PRECISION_LIMIT) as isize;
+            d.round(n);
+            positional(f, sign, &d, p)
+        }
+        (Notation::Positional, None) => {
+            let d = Decimal::shortest(x);
+            positional(f, sign, &d, d.frac_digits())
+        }
+        (Notation::Debug, None) => {
+            let d = Decimal::shortest(x);
+            if d.digits.is_empty() || d.exp >= -4 && d.exp < 16 {
+                positional(f, sign, &d, d.frac_digits().max(1))
+            } else {
+                scientific(f, sign, &d, d.digits.len() - 1, "e")
+            }
+        }
+    }
+}
+
+/// Writes the shortest decimal that parses back to the same value, in positional notation like
+/// the primitive floats. With a precision, writes the exact value rounded to nearest, ties to
+/// even, with that many fraction digits instead. Width, fill, alignment and the `+` and `0` flags
+/// work as they do for `f64`; infinities and NaN are written as `inf` and `NaN`.
+impl fmt::Display for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_decimal(*self, f, Notation::Positional)
+    }
+}
+
+/// Like `Display`, in scientific notation such as `1.5e-7`, with the precision counting the
+/// digits after the point.
+impl fmt::LowerExp for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_decimal(*self, f, Notation::Scientific("e"))
+    }
+}
+
+/// Like `LowerExp`, with an `E` for the exponent.
+impl fmt::UpperExp for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_decimal(*self, f, Notation::Scientific("E"))
+    }
+}
+
+/// Like `Display`, but always with a point, as in `1.0`, and in scientific notation below 1e-4
+/// and from 1e16 on, as `f64` does.
+impl fmt::Debug for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_decimal(*self, f, Notation::Debug)
+    }
+}
@@ -0,0 +1,187 @@
+//! Decomposing, scaling and rounding `f128` values with libquadmath's functions of the same names.
+
+use convert;
+use f128_t::f128;
+use ffi;
+use flags::{self, FpFlags};
+use libc::{c_int, c_long};
+use num_traits::Float;
+use rounding::{self, RoundingMode};
+use soft;
+
+/// The bounds of `i64`, `-2^63` and `2^63`.
+const I64_MIN: f128 = f128!(-9223372036854775808);
+const I64_END: f128 = f128!(9223372036854775808);
+
+impl f128 {
+    /// Splits `self` into a fraction with a magnitude in `[0.5, 1)` and a power of two, so that
+    /// `self = fraction * 2^exponent`. Zeros, infinities and NaNs are returned as they are, with
+    /// an exponent of 0.
+    pub fn frexp(self) -> (f128, i32) {
+        let mut exponent: c_int = 0;
+        let fraction = unsafe { ffi::frexpq_f(self, &mut exponent) };
+        if !self.is_finite() {
+            exponent = 0;
+        }
+        (fraction, exponent)
+    }
+
+    /// `self * 2^n`, rounded like the arithmetic operators if it underflows, and the inverse of
+    /// `frexp`. The same as `scalbn`.
+    #[inline]
+    pub fn ldexp(self, n: i32) -> f128 {
+        match rounding::soft_mode() {
+            None => unsafe { ffi::ldexpq_f(self, n) },
+            Some(_) => self.scale_by_pow2(n),
+        }
+    }
+
+    /// `self * 2^n`, rounded like the arithmetic operators if it underflows.
+    #[inline]
+    pub fn scalbn(self, n: i32) -> f128 {
+        match rounding::soft_mode() {
+            None => unsafe { ffi::scalbnq_f(self, n) },
+            Some(_) => self.scale_by_pow2(n),
+        }
+    }
+
+    /// `scalbn` with a 64-bit exponent. Any `n` beyond the range of `i32` overflows or
+    /// underflows every finite nonzero value.
+    pub fn scalbln(self, n: i64) -> f128 {
+        match rounding::soft_mode() {
+            // `long` only has 32 bits on some targets; saturating doesn't change the result.
+            None => {
+                let n = n
+                    .max(c_long::min_value() as i64)
+                    .min(c_long::max_value() as i64);
+                unsafe { ffi::scalblnq_f(self, n as c_long) }
+            }
+            Some(_) => {
+                let n = n.max(i32::min_value() as i64).min(i32::max_value() as i64);
+                self.scale_by_pow2(n as i32)
+            }
+        }
+    }
+
+    /// Splits `self` into its fractional and integral parts, both with the sign of `self`, in
+    /// that order. Infinities have a fractional part of 0, and NaNs give NaN for both.
+    pub fn modf(self) -> (f128, f128) {
+        let mut integral = f128::ZERO;
+        let fractional = unsafe { ffi::modfq_f(self, &mut integral) };
+        (fractional, integral)
+    }
+
+    /// The exponent of `self` as an integer: `e` for normal and subnormal values in
+    /// `[2^e, 2^(e+1))`. `None` for zeros, infinities and NaNs, raising the invalid exception.
+    pub fn ilogb(self) -> Option<i32> {
+        if self.is_finite() && self != f128::ZERO {
+            Some(unsafe { ffi::ilogbq_f(self) })
+        } else {
+            flags::raise(FpFlags::INVALID);
+            None
+        }
+    }
+
+    /// The exponent of `self` like `ilogb`, but as an `f128`: negative infinity for zeros,
+    /// positive infinity for infinities, and NaN for NaNs.
+    #[inline]
+    pub fn logb(self) -> f128 {
+        unsafe { ffi::logbq_f(self) }
+    }
+
+    /// The IEEE 754 remainder `self - n * other`, where `n` is `self / other` rounded to the
+    /// nearest integer, ties to even. It is always exact, and NaN if `self` is infinite or
+    /// `other` is zero.
+    #[inline]
+    pub fn remainder(self, other: f128) -> f128 {
+        unsafe { ffi::remainderq_f(self, other) }
+    }
+
+    /// The `remainder` of `self` and `other`, with the sign and at least the three lowest bits
+    /// of the quotient `n` it subtracts a multiple of `other` for.
+    pub fn remquo(self, other: f128) -> (f128, i32) {
+        let mut quotient: c_int = 0;
+        let remainder = unsafe { ffi::remquoq_f(self, other, &mut quotient) };
+        (remainder, quotient)
+    }
+
+    /// The positive difference `self - other` if `self` is greater than `other`, and `+0`
+    /// otherwise. NaN if either is NaN.
+    pub fn fdim(self, other: f128) -> f128 {
+        match rounding::soft_mode() {
+            None => unsafe { ffi::fdimq_f(self, other) },
+            Some(_) if self.is_nan() || other.is_nan() => soft::nan_result(self, other),
+            Some(_) if self > other => self - other,
+            Some(_) => f128::ZERO,
+        }
+    }
+
+    /// The magnitude of `self` with the sign of `sign`, NaNs included.
+    #[inline]
+    pub fn copysign(self, sign: f128) -> f128 {
+        unsafe { ffi::copysignq_f(self, sign) }
+    }
+
+    /// `self` rounded to an integer in the current rounding mode, without raising the inexact
+    /// exception.
+    pub fn nearbyint(self) -> f128 {
+        match rounding::soft_mode() {
+            None => unsafe { ffi::nearbyintq_f(self) },
+            Some(mode) => round_in(self, mode),
+        }
+    }
+
+    /// `self` rounded to an integer in the current rounding mode, raising the inexact exception
+    /// if that changes its value.
+    pub fn rint(self) -> f128 {
+        match rounding::soft_mode() {
+            None => unsafe { ffi::rintq_f(self) },
+            Some(mode) => {
+                let rounded = round_in(self, mode);
+                if self.is_finite() && rounded != self {
+                    flags::raise(FpFlags::INEXACT);
+                }
+                rounded
+            }
+        }
+    }
+
+    /// `self` rounded to an integer in the current rounding mode like `rint`, as an `i64`. `None`
+    /// for NaNs and values out of range, raising the invalid exception.
+    pub fn lrint(self) -> Option<i64> {
+        let rounded = self.rint();
+        if !(rounded >= I64_MIN && rounded < I64_END) {
+            flags::raise(FpFlags::INVALID);
+            return None;
+        }
+        match rounding::soft_mode() {
+            // `llrintq` rather than `lrintq`, which only returns 32 bits on some targets.
+            None => Some(unsafe { ffi::llrintq_f(self) } as i64),
+            Some(_) => convert::truncated(rounded),
+        }
+    }
+
+    /// `self` rounded to the nearest integer, ties away from zero, as an `i64`. `None` for NaNs
+    /// and values out of range, raising the invalid exception.
+    pub fn llround(self) -> Option<i64> {
+        let rounded = self.round();
+        if !(rounded >= I64_MIN && rounded < I64_END) {
+            flags::raise(FpFlags::INVALID);
+            return None;
+        }
+        Some(unsafe { ffi::llroundq_f(self) } as i64)
+    }
+}
+
+/// `x` rounded to an integer in `mode`.
+fn round_in(x: f128, mode: RoundingMode) -> f128 {
+    if x.is_nan() {
+        return soft::nan_result(x, x);
+    }
+    match mode {
+        RoundingMode::NearestEven => unsafe { ffi::nearbyintq_f(x) },
+        RoundingMode::TowardZero => x.trunc(),
+        RoundingMode::Up => x.ceil(),
+        RoundingMode::Down => x.floor(),
+    }
+}
@@ -155,6 +155,9 @@ void f128_to_str(Wrapper a, size_t n, char* buf, const char* format) {
 }
  Wrapper expm1q_f (Wrapper a) {
   return (Wrapper) { expm1q(a.value) };
+}
+ Wrapper exp2q_f (Wrapper a) {
+  return (Wrapper) { exp2q(a.value) };
 }
  Wrapper fabsq_f (Wrapper a) {
   return (Wrapper) { fabsq(a.value) };
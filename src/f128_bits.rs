@@ -0,0 +1,147 @@
+//! A view of the bits of an `f128` as an integer, for bit manipulation.
+
+use f128_t::f128;
+use std::fmt;
+use std::ops::*;
+
+/// The IEEE 754 binary128 encoding of an `f128`, laid out as `f128::from_bits` describes, with
+/// the bitwise operators of an integer.
+///
+/// `f128` itself only has arithmetic operators; this is the type to mask, combine and shift its
+/// bits with. Conversions in both directions are lossless, NaN payloads included.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct F128Bits(pub u128);
+
+impl F128Bits {
+    /// The sign bit.
+    pub const SIGN_MASK: F128Bits = F128Bits(1 << 127);
+    /// The 15 bits of the biased exponent.
+    pub const EXPONENT_MASK: F128Bits = F128Bits(0x7fff << 112);
+    /// The 112 bits of the fraction.
+    pub const FRACTION_MASK: F128Bits = F128Bits((1 << 112) - 1);
+
+    /// The bits of `x`.
+    #[inline]
+    pub const fn from_f128(x: f128) -> F128Bits {
+        F128Bits(x.to_bits())
+    }
+
+    /// The `f128` with these bits.
+    #[inline]
+    pub const fn to_f128(self) -> f128 {
+        f128::from_bits(self.0)
+    }
+}
+
+impl From<f128> for F128Bits {
+    #[inline]
+    fn from(x: f128) -> F128Bits {
+        F128Bits::from_f128(x)
+    }
+}
+
+impl From<F128Bits> for f128 {
+    #[inline]
+    fn from(bits: F128Bits) -> f128 {
+        bits.to_f128()
+    }
+}
+
+impl From<u128> for F128Bits {
+    #[inline]
+    fn from(bits: u128) -> F128Bits {
+        F128Bits(bits)
+    }
+}
+
+impl From<F128Bits> for u128 {
+    #[inline]
+    fn from(bits: F128Bits) -> u128 {
+        bits.0
+    }
+}
+
+macro_rules! bitwise_impl {
+    ($($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident, $op:tt;)*) => ($(
+        impl $imp for F128Bits {
+            type Output = F128Bits;
+
+            #[inline]
+            fn $method(self, other: F128Bits) -> F128Bits {
+                F128Bits(self.0 $op other.0)
+            }
+        }
+
+        impl $assign_imp for F128Bits {
+            #[inline]
+            fn $assign_method(&mut self, other: F128Bits) {
+                self.0 = self.0 $op other.0;
+            }
+        }
+    )*)
+}
+
+bitwise_impl! {
+    BitAnd, bitand, BitAndAssign, bitand_assign, &;
+    BitOr, bitor, BitOrAssign, bitor_assign, |;
+    BitXor, bitxor, BitXorAssign, bitxor_assign, ^;
+}
+
+impl Not for F128Bits {
+    type Output = F128Bits;
+
+    #[inline]
+    fn not(self) -> F128Bits {
+        F128Bits(!self.0)
+    }
+}
+
+macro_rules! shift_impl {
+    ($($imp:ident, $method:ident, $assign_imp:ident, $assign_method:ident, $op:tt;)*) => ($(
+        /// Shifts like `u128` does, so shifting by 128 or more bits overflows.
+        impl $imp<u32> for F128Bits {
+            type Output = F128Bits;
+
+            #[inline]
+            fn $method(self, n: u32) -> F128Bits {
+                F128Bits(self.0 $op n)
+            }
+        }
+
+        impl $assign_imp<u32> for F128Bits {
+            #[inline]
+            fn $assign_method(&mut self, n: u32) {
+                self.0 = self.0 $op n;
+            }
+        }
+    )*)
+}
+
+shift_impl! {
+    Shl, shl, ShlAssign, shl_assign, <<;
+    Shr, shr, ShrAssign, shr_assign, >>;
+}
+
+impl fmt::Debug for F128Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "F128Bits({:#034x})", self.0)
+    }
+}
+
+impl fmt::LowerHex for F128Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::UpperHex for F128Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Binary for F128Bits {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Binary::fmt(&self.0, f)
+    }
+}
@@ -1,30 +1,22 @@
 use f128_t::*;
 use ffi;
 use ffi::*;
+use flags::{self, FpFlags};
+use num_traits::AsPrimitive;
+use rounding;
+use soft;
 use std::cmp::Ordering::*;
 use std::cmp::*;
 use std::convert::{From, Into};
 use std::ffi::CString;
-use std::fmt::{Debug, Error, Formatter};
-use std::hash::{Hash, Hasher};
 use std::iter::*;
-use std::mem;
 use std::ops::*;
-use std::slice;
-
-impl Debug for f128 {
-    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
-        f.write_str(&self.to_string())
-    }
-}
 
 impl Neg for f128 {
     type Output = Self;
 
-    fn neg(mut self) -> Self {
-        let mut bits = self.inner_as_u128();
-        bits ^= f128::SIGN_BIT.inner_as_u128();
-        f128::from_raw_u128(bits)
+    fn neg(self) -> Self {
+        f128::from_bits(self.to_bits() ^ (1 << 127))
     }
 }
 
@@ -58,126 +50,15 @@ macro_rules! forward_ref_binop {
     };
 }
 
-macro_rules! shl_impl {
-    ($t:ty, $f:ty) => {
-        impl Shr<$f> for $t {
-            type Output = $t;
-
-            #[inline]
-            fn shr(self, other: $f) -> f128 {
-                unsafe {
-                    mem::transmute::<u128, f128>(
-                        (mem::transmute::<[u8; 16], u128>(self.inner()) >> other),
-                    )
-                }
-            }
-        }
-        forward_ref_binop! { impl Shr, shr for $t, $f }
-    };
-}
-
-shl_impl! { f128, u8 }
-shl_impl! { f128, u16 }
-shl_impl! { f128, u32 }
-shl_impl! { f128, u64 }
-shl_impl! { f128, u128 }
-shl_impl! { f128, usize }
-
-shl_impl! { f128, i8 }
-shl_impl! { f128, i16 }
-shl_impl! { f128, i32 }
-shl_impl! { f128, i64 }
-shl_impl! { f128, i128 }
-shl_impl! { f128, isize }
-
-macro_rules! shr_assign_impl {
-    ($t:ty, $f:ty) => {
-        impl ShrAssign<$f> for $t {
-            #[inline]
-            fn shr_assign(&mut self, other: $f) {
-                *self = *self >> other
-            }
-        }
-    };
-}
-
-shr_assign_impl! {f128, u8 }
-shr_assign_impl! {f128, u16 }
-shr_assign_impl! {f128, u32 }
-shr_assign_impl! {f128, u64 }
-shr_assign_impl! {f128, u128 }
-shr_assign_impl! {f128, usize }
-
-shr_assign_impl! {f128, i8 }
-shr_assign_impl! {f128, i16 }
-shr_assign_impl! {f128, i32 }
-shr_assign_impl! {f128, i64 }
-shr_assign_impl! {f128, i128 }
-shr_assign_impl! {f128, isize }
-
-macro_rules! shl_impl {
-    ($t:ty, $f:ty) => {
-        impl Shl<$f> for $t {
-            type Output = $t;
-
-            #[inline]
-            fn shl(self, other: $f) -> $t {
-                unsafe {
-                    mem::transmute::<u128, f128>(
-                        (mem::transmute::<[u8; 16], u128>(self.inner()) << other),
-                    )
-                }
-            }
-        }
-        forward_ref_binop! { impl Shl, shl for $t, $f }
-    };
-}
-
-shl_impl! { f128, u8 }
-shl_impl! { f128, u16 }
-shl_impl! { f128, u32 }
-shl_impl! { f128, u64 }
-shl_impl! { f128, u128 }
-shl_impl! { f128, usize }
-
-shl_impl! { f128, i8 }
-shl_impl! { f128, i16 }
-shl_impl! { f128, i32 }
-shl_impl! { f128, i64 }
-shl_impl! { f128, i128 }
-shl_impl! { f128, isize }
-
-macro_rules! shl_assign_impl {
-    ($t:ty, $f:ty) => {
-        impl ShlAssign<$f> for $t {
-            #[inline]
-            fn shl_assign(&mut self, other: $f) {
-                *self = *self << other
-            }
-        }
-    };
-}
-
-shl_assign_impl! {f128, u8 }
-shl_assign_impl! {f128, u16 }
-shl_assign_impl! {f128, u32 }
-shl_assign_impl! {f128, u64 }
-shl_assign_impl! {f128, u128 }
-shl_assign_impl! {f128, usize }
-
-shl_assign_impl! {f128, i8 }
-shl_assign_impl! {f128, i16 }
-shl_assign_impl! {f128, i32 }
-shl_assign_impl! {f128, i64 }
-shl_assign_impl! {f128, i128 }
-shl_assign_impl! {f128, isize }
-
 impl Add for f128 {
     type Output = f128;
 
     #[inline]
     fn add(self, other: f128) -> f128 {
-        unsafe { ffi::f128_add(self, other) }
+        match rounding::soft_mode() {
+            None => unsafe { ffi::f128_add(self, other) },
+            Some(mode) => soft::add(self, other, mode),
+        }
     }
 }
 
@@ -195,7 +76,10 @@ impl Sub for f128 {
 
     #[inline]
     fn sub(self, other: f128) -> f128 {
-        unsafe { ffi::f128_sub(self, other) }
+        match rounding::soft_mode() {
+            None => unsafe { ffi::f128_sub(self, other) },
+            Some(mode) => soft::sub(self, other, mode),
+        }
     }
 }
 
@@ -213,7 +97,10 @@ impl Mul for f128 {
 
     #[inline]
     fn mul(self, other: f128) -> f128 {
-        unsafe { ffi::f128_mul(self, other) }
+        match rounding::soft_mode() {
+            None => unsafe { ffi::f128_mul(self, other) },
+            Some(mode) => soft::mul(self, other, mode),
+        }
     }
 }
 
@@ -231,7 +118,10 @@ impl Div for f128 {
 
     #[inline]
     fn div(self, other: f128) -> f128 {
-        unsafe { ffi::f128_div(self, other) }
+        match rounding::soft_mode() {
+            None => unsafe { ffi::f128_div(self, other) },
+            Some(mode) => soft::div(self, other, mode),
+        }
     }
 }
 
@@ -285,36 +175,18 @@ impl<'a> Product<&'a f128> for f128 {
     }
 }
 
-impl Hash for f128 {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_i128(unsafe { mem::transmute::<[u8; 16], i128>(self.inner()) })
-    }
-    fn hash_slice<H: Hasher>(data: &[f128], state: &mut H) {
-        let newlen = data.len() * mem::size_of::<f128>();
-        let ptr = data.as_ptr() as *const u8;
-        state.write(unsafe { slice::from_raw_parts(ptr, newlen) })
-    }
-}
-
 macro_rules! impl_from_to {
-    ($($to:ident, $from:ident - $ty:ty),*) => ($(
+    ($($from:path => $ty:ty),*) => ($(
         impl From<$ty> for f128 {
             #[inline]
             fn from(small: $ty) -> f128 {
                 unsafe { $from(small) }
             }
         }
-        impl Into<$ty> for f128 {
-            #[inline]
-            fn into(self) -> $ty {
-               unsafe { $to(self) }
-            }
-        }
-
         impl F128 for $ty {
             #[inline]
             fn from_f128(x: f128) -> Self {
-                unsafe { $to(x) }
+                x.as_()
             }
             #[inline]
             fn f128(self) -> f128 {
@@ -326,20 +198,34 @@ macro_rules! impl_from_to {
 }
 
 impl_from_to! {
-    f128_to_isize,   isize_to_f128  - isize,
-    f128_to_i8,      i8_to_f128     - i8,
-    f128_to_i16,     i16_to_f128    - i16,
-    f128_to_i32,     i32_to_f128    - i32,
-    f128_to_i64,     i64_to_f128    - i64,
-    f128_to_i128,    i128_to_f128   - i128,
-    f128_to_usize,   usize_to_f128  - usize,
-    f128_to_u8,      u8_to_f128     - u8,
-    f128_to_u16,     u16_to_f128    - u16,
-    f128_to_u32,     u32_to_f128    - u32,
-    f128_to_u64,     u64_to_f128    - u64,
-    f128_to_u128,    u128_to_f128   - u128,
-    f128_to_f32,     f32_to_f128    - f32,
-    f128_to_f64,     f64_to_f128    - f64
+    isize_to_f128  => isize,
+    i8_to_f128     => i8,
+    i16_to_f128    => i16,
+    i32_to_f128    => i32,
+    i64_to_f128    => i64,
+    rounding::i128_to_f128 => i128,
+    usize_to_f128  => usize,
+    u8_to_f128     => u8,
+    u16_to_f128    => u16,
+    u32_to_f128    => u32,
+    u64_to_f128    => u64,
+    rounding::u128_to_f128 => u128,
+    f32_to_f128 => f32,
+    f64_to_f128 => f64
+}
+
+impl Into<f32> for f128 {
+    #[inline]
+    fn into(self) -> f32 {
+        rounding::f128_to_f32(self)
+    }
+}
+
+impl Into<f64> for f128 {
+    #[inline]
+    fn into(self) -> f64 {
+        rounding::f128_to_f64(self)
+    }
 }
 
 pub trait F128 {
@@ -353,7 +239,10 @@ impl PartialOrd for f128 {
         let gte = unsafe { gteq(*self, *other) };
 
         match (lte != 0, gte != 0) {
-            (false, false) => None,
+            (false, false) => {
+                flags::raise(FpFlags::INVALID);
+                None
+            }
             (false, true) => Some(Greater),
             (true, false) => Some(Less),
             (true, true) => Some(Equal),
@@ -363,10 +252,20 @@ impl PartialOrd for f128 {
 
 impl PartialEq for f128 {
     fn eq(&self, other: &Self) -> bool {
+        signal_equality(*self, *other);
         unsafe { eqq(*self, *other) != 0 }
     }
 
     fn ne(&self, other: &Self) -> bool {
+        signal_equality(*self, *other);
         unsafe { neqq(*self, *other) != 0 }
     }
 }
+
+/// Equality comparisons are quiet: only signalling NaNs raise the invalid exception.
+#[inline]
+fn signal_equality(x: f128, y: f128) {
+    if flags::recording() && (soft::is_signaling(x) || soft::is_signaling(y)) {
+        flags::raise(FpFlags::INVALID);
+    }
+}
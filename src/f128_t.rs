@@ -1,9 +1,14 @@
+use convert;
 use f128_derive::*;
 use ffi;
 use ffi::*;
+use flags::{self, FpFlags};
 use libc::c_int;
 use num_traits::*;
-use std::convert::{From, Into};
+use parse::{self, ParseF128Error};
+use rounding;
+use soft;
+use std::convert::{From, Into, TryFrom};
 use std::ffi::CString;
 use std::ffi::NulError;
 use std::fmt;
@@ -15,6 +20,7 @@ use std::num::FpCategory;
 use std::ops::*;
 use std::slice;
 use std::str;
+use std::str::FromStr;
 
 macro_rules! f128_from_x {
     ($x: ty, $n: expr, $it: expr) => {{
@@ -30,168 +36,169 @@ macro_rules! f128_from_x {
 pub struct f128(pub(crate) [u8; 16]);
 
 impl f128 {
-    pub const RADIX: u32 = 128;
-    pub const MANTISSA_DIGITS: u32 = 112;
+    /// The radix of the internal representation.
+    pub const RADIX: u32 = 2;
 
+    /// Significand digits in base 2, including the implicit leading bit.
+    pub const MANTISSA_DIGITS: u32 = 113;
+
+    /// Decimal digits that survive a round trip through `f128` and back.
+    pub const DIGITS: u32 = 33;
+
+    /// The largest `n` such that 10<sup>n</sup> is finite.
     pub const MAX_10_EXP: u32 = 4932;
+
+    /// The binary exponent of the largest finite values, IEEE 754's emax. Unlike `f64::MAX_EXP`,
+    /// which counts one more, 2<sup>MAX_EXP</sup> itself is finite.
     pub const MAX_EXP: u32 = 16383;
+
+    /// The smallest `n` such that 10<sup>n</sup> is normal.
     pub const MIN_10_EXP: i32 = -4931;
+
+    /// The binary exponent of the smallest normal values, IEEE 754's emin. Unlike `f64::MIN_EXP`,
+    /// which counts one more, `MIN_POSITIVE` is 2<sup>MIN_EXP</sup>.
     pub const MIN_EXP: i32 = -16382;
-    pub const ZERO: f128 = f128([0; 16]);
 
-    #[cfg(target_endian = "big")]
-    pub const SIGN_BIT: f128 = f128([
-        0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const SIGN_BIT: f128 = f128([
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x80,
-    ]);
+    /// 0
+    pub const ZERO: f128 = f128::from_bits(0x0000_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "big")]
-    pub const EXPONENT_BITS: f128 = f128([
-        0x7f, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const EXPONENT_BITS: f128 = f128([
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff,
-        0x7f,
-    ]);
+    /// A mask of the sign bit.
+    pub const SIGN_BIT: f128 = f128::from_bits(0x8000_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "big")]
-    pub const FRACTION_BITS: f128 = f128([
-        0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
-        0xFF,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const FRACTION_BITS: f128 = f128([
-        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00,
-        0x00,
-    ]);
+    /// A mask of the exponent bits.
+    pub const EXPONENT_BITS: f128 = f128::from_bits(0x7fff_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "big")]
-    pub const MIN: f128 = f128([
-        0xff, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
-        0xff,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const MIN: f128 = f128([
-        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
-        0xff,
-    ]);
+    /// A mask of the trailing significand bits.
+    pub const FRACTION_BITS: f128 = f128::from_bits(0x0000_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
 
-    #[cfg(target_endian = "big")]
-    pub const MIN_POSITIVE: f128 = f128([
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x01,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const MIN_POSITIVE: f128 = f128([
-        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00,
-    ]);
+    /// The smallest finite value, `-MAX`.
+    pub const MIN: f128 = f128::from_bits(0xfffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
 
-    #[cfg(target_endian = "big")]
-    pub const ONE: f128 = f128([
-        0x3f, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const ONE: f128 = f128([
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff,
-        0x3f,
-    ]);
+    /// The smallest positive normal value, 2<sup>-16382</sup>.
+    pub const MIN_POSITIVE: f128 = f128::from_bits(0x0001_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "big")]
-    pub const TWO: f128 = f128([
-        0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x00,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const TWO: f128 = f128([
-        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-        0x40,
-    ]);
+    /// The smallest positive value, the subnormal 2<sup>-16494</sup>.
+    pub const MIN_POSITIVE_SUBNORMAL: f128 =
+        f128::from_bits(0x0000_0000_0000_0000_0000_0000_0000_0001);
 
-    #[cfg(target_endian = "big")]
-    pub const E: f128 = f128([
-        0x40, 0x00, 0x5b, 0xf0, 0xa8, 0xb1, 0x45, 0x76, 0x95, 0x35, 0x5f, 0xb8, 0xac, 0x40, 0x4e,
-        0x7a,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const E: f128 = f128([
-        0x7a, 0x4e, 0x40, 0xac, 0xb8, 0x5f, 0x35, 0x95, 0x76, 0x45, 0xb1, 0xa8, 0xf0, 0x5b, 0x00,
-        0x40,
-    ]);
+    /// 1
+    pub const ONE: f128 = f128::from_bits(0x3fff_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "big")]
-    pub const PI: f128 = f128([
-        0x40, 0x00, 0x92, 0x1f, 0xb5, 0x44, 0x42, 0xd1, 0x84, 0x69, 0x89, 0x8c, 0xc5, 0x17, 0x01,
-        0xb8,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const PI: f128 = f128([
-        0xb8, 0x01, 0x17, 0xc5, 0x8c, 0x89, 0x69, 0x84, 0xd1, 0x42, 0x44, 0xb5, 0x1f, 0x92, 0x00,
-        0x40,
-    ]);
+    /// 2
+    pub const TWO: f128 = f128::from_bits(0x4000_0000_0000_0000_0000_0000_0000_0000);
 
-    #[cfg(target_endian = "little")]
-    pub const INFINITY: f128 = f128([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0x7f]);
-    #[cfg(target_endian = "big")]
-    pub const INFINITY: f128 = f128([0x7F, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// Euler's number e
+    pub const E: f128 = f128::from_bits(0x4000_5bf0_a8b1_4576_9535_5fb8_ac40_4e7a);
 
-    #[cfg(target_endian = "big")]
-    pub const NAN: f128 = f128([
-        0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFFu8,
-        0xFFu8, 0xFF,
-    ]);
-    #[cfg(target_endian = "little")]
-    pub const NAN: f128 = f128([
-        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFFu8,
-        0xFFu8, 0x7F,
-    ]);
+    /// Archimedes' constant π
+    pub const PI: f128 = f128::from_bits(0x4000_921f_b544_42d1_8469_898c_c517_01b8);
 
-    #[cfg(target_endian = "little")]
-    pub const NEG_INFINITY: f128 = f128([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff]);
-    #[cfg(target_endian = "big")]
-    pub const NEG_INFINITY: f128 = f128([0xff, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// τ = 2π
+    pub const TAU: f128 = f128::from_bits(0x4001_921f_b544_42d1_8469_898c_c517_01b8);
 
-    #[cfg(target_endian = "little")]
-    pub const EPSILON: f128 = f128([
-        0x65, 0x64, 0xD2, 0x5B, 0x93, 0xF2, 0x61, 0x43, 0, 0x51, 0x2F, 0x7F, 0x8A, 0, 0x8F, 0x3F,
-    ]);
-    #[cfg(target_endian = "big")]
-    pub const EPSILON: f128 = f128([
-        0x3F, 0x8F, 0x0, 0x8A, 0x7F, 0x2F, 0x51, 0x0, 0x43, 0x61, 0xF2, 0x93, 0x5B, 0xD2, 0x64,
-        0x65,
-    ]);
+    /// π/2
+    pub const FRAC_PI_2: f128 = f128::from_bits(0x3fff_921f_b544_42d1_8469_898c_c517_01b8);
 
-    #[cfg(target_endian = "little")]
-    pub const NEG_ZERO: f128 = f128([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x80]);
-    #[cfg(target_endian = "big")]
-    pub const NEG_ZERO: f128 = f128([0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    /// π/3
+    pub const FRAC_PI_3: f128 = f128::from_bits(0x3fff_0c15_2382_d736_5846_5bb3_2e0f_567b);
 
-    #[cfg(target_endian = "little")]
-    pub const MAX: f128 = f128([
-        0x7f, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
-        0xff,
-    ]);
-    #[cfg(target_endian = "big")]
-    pub const MAX: f128 = f128([
-        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
-        0x7f,
-    ]);
+    /// π/4
+    pub const FRAC_PI_4: f128 = f128::from_bits(0x3ffe_921f_b544_42d1_8469_898c_c517_01b8);
+
+    /// π/6
+    pub const FRAC_PI_6: f128 = f128::from_bits(0x3ffe_0c15_2382_d736_5846_5bb3_2e0f_567b);
+
+    /// π/8
+    pub const FRAC_PI_8: f128 = f128::from_bits(0x3ffd_921f_b544_42d1_8469_898c_c517_01b8);
+
+    /// 1/π
+    pub const FRAC_1_PI: f128 = f128::from_bits(0x3ffd_45f3_06dc_9c88_2a53_f84e_afa3_ea6a);
+
+    /// 2/π
+    pub const FRAC_2_PI: f128 = f128::from_bits(0x3ffe_45f3_06dc_9c88_2a53_f84e_afa3_ea6a);
+
+    /// 2/sqrt(π)
+    pub const FRAC_2_SQRT_PI: f128 = f128::from_bits(0x3fff_20dd_7504_29b6_d11a_e3a9_14fe_d7fe);
+
+    /// sqrt(2)
+    pub const SQRT_2: f128 = f128::from_bits(0x3fff_6a09_e667_f3bc_c908_b2fb_1366_ea95);
+
+    /// 1/sqrt(2)
+    pub const FRAC_1_SQRT_2: f128 = f128::from_bits(0x3ffe_6a09_e667_f3bc_c908_b2fb_1366_ea95);
+
+    /// sqrt(3)
+    pub const SQRT_3: f128 = f128::from_bits(0x3fff_bb67_ae85_84ca_a73b_2574_2d70_78b8);
+
+    /// ln(2)
+    pub const LN_2: f128 = f128::from_bits(0x3ffe_62e4_2fef_a39e_f357_93c7_6730_07e6);
+
+    /// ln(10)
+    pub const LN_10: f128 = f128::from_bits(0x4000_26bb_1bbb_5551_582d_d4ad_ac57_05a6);
+
+    /// ln(π)
+    pub const LN_PI: f128 = f128::from_bits(0x3fff_250d_048e_7a1b_d0bd_5f95_6c6a_843f);
+
+    /// log2(e)
+    pub const LOG2_E: f128 = f128::from_bits(0x3fff_7154_7652_b82f_e177_7d0f_fda0_d23a);
+
+    /// log10(e)
+    pub const LOG10_E: f128 = f128::from_bits(0x3ffd_bcb7_b152_6e50_e32a_6ab7_555f_5a68);
+
+    /// log2(10)
+    pub const LOG2_10: f128 = f128::from_bits(0x4000_a934_f097_9a37_15fc_9257_edfe_9b60);
+
+    /// log10(2)
+    pub const LOG10_2: f128 = f128::from_bits(0x3ffd_3441_3509_f79f_ef31_1f12_b358_16f9);
+
+    /// The Euler–Mascheroni constant γ
+    pub const EGAMMA: f128 = f128::from_bits(0x3ffe_2788_cfc6_fb61_8f49_a37c_7f02_02a6);
+
+    /// Catalan's constant G
+    pub const CATALAN: f128 = f128::from_bits(0x3ffe_d4f9_713e_8135_d08a_42b0_45c6_fa66);
+
+    /// The golden ratio φ = (1 + sqrt(5))/2
+    pub const PHI: f128 = f128::from_bits(0x3fff_9e37_79b9_7f4a_7c15_f39c_c060_5cee);
+
+    /// Positive infinity
+    pub const INFINITY: f128 = f128::from_bits(0x7fff_0000_0000_0000_0000_0000_0000_0000);
+
+    /// A quiet NaN
+    pub const NAN: f128 = f128::from_bits(0x7fff_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+
+    /// Negative infinity
+    pub const NEG_INFINITY: f128 = f128::from_bits(0xffff_0000_0000_0000_0000_0000_0000_0000);
+
+    /// The difference between 1 and the next larger `f128`, 2<sup>-112</sup>.
+    pub const EPSILON: f128 = f128::from_bits(0x3f8f_0000_0000_0000_0000_0000_0000_0000);
+
+    /// -0
+    pub const NEG_ZERO: f128 = f128::from_bits(0x8000_0000_0000_0000_0000_0000_0000_0000);
+
+    /// The largest finite value, (2 - 2<sup>-112</sup>) × 2<sup>16383</sup>.
+    pub const MAX: f128 = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+
+    /// The `f128` whose IEEE 754 binary128 encoding is `bits`: the sign in bit 127, the biased
+    /// exponent in bits 112 to 126 and the fraction in bits 0 to 111. Unlike the bytes of
+    /// `inner`, this does not depend on the target's endianness.
+    #[inline]
+    pub const fn from_bits(bits: u128) -> f128 {
+        f128(bits.to_ne_bytes())
+    }
+
+    /// The IEEE 754 binary128 encoding of `self`, laid out as `from_bits` describes.
+    #[inline]
+    pub const fn to_bits(self) -> u128 {
+        u128::from_ne_bytes(self.0)
+    }
 
     pub(crate) fn from_arr(d: [u8; 16]) -> Self {
         f128(d)
     }
 
+    #[deprecated(note = "use `from_bits`")]
     #[inline(always)]
     pub(crate) fn from_raw_u128(d: u128) -> Self {
-        f128::from_arr(unsafe { mem::transmute::<u128, [u8; 16]>(d) })
+        f128::from_bits(d)
     }
     #[inline(always)]
     pub(crate) fn from_raw_i128(d: i128) -> Self {
@@ -203,9 +210,10 @@ impl f128 {
         unsafe { mem::transmute::<[u8; 16], i128>(self.0) }
     }
 
+    #[deprecated(note = "use `to_bits`")]
     #[inline(always)]
     pub(crate) fn inner_as_u128(&self) -> u128 {
-        unsafe { mem::transmute::<[u8; 16], u128>(self.0) }
+        self.to_bits()
     }
 
     #[inline(always)]
@@ -213,11 +221,12 @@ impl f128 {
         a.into()
     }
 
-    #[inline]
-    pub fn to_string(&self) -> String {
-        self.to_string_fmt("%.36Qg").unwrap()
-    }
-
+    /// Formats `self` with libquadmath's `quadmath_snprintf` and the C format string `fmt`, such as
+    /// `"%.30Qe"`, or `None` if `fmt` contains a nul byte.
+    ///
+    /// Not available with the `soft-float` feature, which builds without libquadmath; the
+    /// `Display`, `LowerExp` and `LowerHex` implementations cover the same formats.
+    #[cfg(not(feature = "soft-float"))]
     pub fn to_string_fmt<T: AsRef<str>>(&self, fmt: T) -> Option<String> {
         let mut buf = [0u8; 128];
         let cstr;
@@ -243,45 +252,204 @@ impl f128 {
         self.0
     }
 
+    /// Parses `s` with libquadmath's `strtoflt128`, which stops at the first character that does
+    /// not belong to a number and returns zero if there is none. Use `str::parse` for strict
+    /// parsing that reports such errors.
+    #[cfg(not(feature = "soft-float"))]
     pub fn parse<T: AsRef<str>>(s: T) -> Result<Self, NulError> {
         let cstr = CString::new(s.as_ref())?;
         let result = unsafe { strtoflt128_f(cstr.as_ptr()) };
 
-        Ok(unsafe { strtoflt128_f(cstr.as_ptr()) })
+        // The crate's own parser raises the exceptions `strtoflt128` raised as it rounds the
+        // same number again.
+        if flags::recording() {
+            let _ = parse::from_str(number_prefix(s.as_ref()));
+        }
+        Ok(result)
+    }
+
+    /// Parses `s` like libquadmath's `strtoflt128` would: stops at the first character that
+    /// does not belong to a number and returns zero if there is none. Use `str::parse` for strict
+    /// parsing that reports such errors.
+    #[cfg(feature = "soft-float")]
+    pub fn parse<T: AsRef<str>>(s: T) -> Result<Self, NulError> {
+        CString::new(s.as_ref())?;
+        let number = number_prefix(s.as_ref());
+        if !number.is_empty() {
+            return Ok(parse::from_c_str(number).unwrap_or(f128::ZERO));
+        }
+        // `inf`, `infinity`, `nan` and `nan(...)`, in any case.
+        let s = s.as_ref().trim_start();
+        let (negative, rest) = match s.as_bytes().first() {
+            Some(&b'-') => (true, &s.as_bytes()[1..]),
+            Some(&b'+') => (false, &s.as_bytes()[1..]),
+            _ => (false, s.as_bytes()),
+        };
+        let starts_with = |word: &[u8]| {
+            rest.len() >= word.len() && rest[..word.len()].eq_ignore_ascii_case(word)
+        };
+        let x = if starts_with(b"inf") {
+            f128::INFINITY
+        } else if starts_with(b"nan") {
+            f128::NAN
+        } else {
+            return Ok(f128::ZERO);
+        };
+        Ok(if negative { -x } else { x })
+    }
+
+    /// The sign field: `true` for negative values, including `-0` and NaNs with the sign set.
+    #[inline]
+    pub const fn is_sign_bit_set(&self) -> bool {
+        self.to_bits() >> 127 != 0
     }
 
+    /// The 15-bit biased exponent field: 0 for zeros and subnormals, `0x7fff` for infinities and
+    /// NaNs, and the exponent plus 16383 otherwise.
     #[inline]
-    pub fn exp_bits(&self) -> u32 {
-        let exp_bits = f128::EXPONENT_BITS.inner_as_u128();
-        ((self.inner_as_u128() & exp_bits) >> 112) as u32
+    pub const fn exp_bits(&self) -> u32 {
+        (self.to_bits() >> 112) as u32 & 0x7fff
     }
 
+    /// The 112-bit fraction field, the significand without its implicit leading bit.
     #[inline]
-    pub fn fract_bits(&self) -> u128 {
-        self.inner_as_u128() & f128::FRACTION_BITS.inner_as_u128()
+    pub const fn fract_bits(&self) -> u128 {
+        self.to_bits() & f128::FRACTION_BITS.to_bits()
     }
-}
 
-impl Default for f128 {
+    /// `self` with the sign field set to `negative`.
     #[inline]
-    fn default() -> f128 {
-        f128::ZERO
+    pub const fn with_sign_bit(self, negative: bool) -> f128 {
+        f128::from_bits(self.to_bits() & !f128::SIGN_BIT.to_bits() | (negative as u128) << 127)
+    }
+
+    /// `self` with the biased exponent field replaced by `exp`.
+    ///
+    /// # Panics
+    ///
+    /// If `exp` does not fit in 15 bits.
+    #[inline]
+    pub const fn with_exp_bits(self, exp: u32) -> f128 {
+        assert!(
+            exp <= 0x7fff,
+            "with_exp_bits: the exponent field has 15 bits"
+        );
+        f128::from_bits(self.to_bits() & !f128::EXPONENT_BITS.to_bits() | (exp as u128) << 112)
+    }
+
+    /// `self` with the fraction field replaced by `fract`.
+    ///
+    /// # Panics
+    ///
+    /// If `fract` does not fit in 112 bits.
+    #[inline]
+    pub const fn with_fract_bits(self, fract: u128) -> f128 {
+        assert!(
+            fract >> 112 == 0,
+            "with_fract_bits: the fraction field has 112 bits"
+        );
+        f128::from_bits(self.to_bits() & !f128::FRACTION_BITS.to_bits() | fract)
+    }
+
+    /// Splits `self` into `(negative, exponent, significand)` such that it equals
+    /// `(-1)^negative * significand * 2^exponent` exactly.
+    ///
+    /// The significand includes the implicit bit, so it has 113 bits for normal numbers and fewer
+    /// for subnormals, which like zero have an exponent of `-16494`. Infinities and NaN decode to
+    /// an exponent of `16272`, with the implicit bit set on top of their fraction bits.
+    pub fn decode(&self) -> (bool, i32, u128) {
+        let negative = self.to_bits() >> 127 != 0;
+        let exp = self.exp_bits() as i32;
+        let fract = self.fract_bits();
+        if exp == 0 {
+            (negative, 1 - 16383 - 112, fract)
+        } else {
+            (negative, exp - 16383 - 112, fract | 1 << 112)
+        }
+    }
+
+    /// The inverse of `decode`: `(-1)^negative * significand * 2^exponent`, rounded according to
+    /// the current `RoundingMode` if it is not representable.
+    ///
+    /// The significand may have any width; a zero significand gives a zero with the given sign.
+    /// Results too large or too small to represent overflow or underflow, raising the
+    /// corresponding exception flags.
+    pub fn from_parts(negative: bool, exponent: i32, significand: u128) -> f128 {
+        soft::from_parts(
+            negative,
+            exponent,
+            significand,
+            rounding::RoundingMode::current(),
+        )
+    }
+
+    /// `self * 2^n`, like C's `ldexpq`: exact unless the result overflows or becomes subnormal, in
+    /// which case it is rounded according to the current `RoundingMode`. Zeros, infinities and
+    /// NaNs are returned unchanged, except that signalling NaNs are quieted.
+    pub fn scale_by_pow2(self, n: i32) -> f128 {
+        if self.is_nan() {
+            return soft::nan_result(self, self);
+        }
+        if self.is_infinite() || self.is_zero() {
+            return self;
+        }
+        let (negative, exponent, significand) = self.decode();
+        f128::from_parts(negative, exponent.saturating_add(n), significand)
     }
 }
 
-impl fmt::Display for f128 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // FIXME: use actual format string and do not
-        // allocate a string
-        write!(f, "{}", self.to_string())
+/// The number `strtoflt128` reads at the start of `s`, without leading whitespace: digits with an
+/// optional sign, point and exponent, in decimal or in hexadecimal after `0x`. Empty if `s` does
+/// not start with one, including when it starts with an infinity or NaN.
+fn number_prefix(s: &str) -> &str {
+    let s = s.trim_start();
+    let bytes = s.as_bytes();
+    let mut i = match bytes.first() {
+        Some(&b'+') | Some(&b'-') => 1,
+        _ => 0,
+    };
+    let hex = bytes[i..].starts_with(b"0x") || bytes[i..].starts_with(b"0X");
+    if hex {
+        i += 2;
+    }
+    let is_digit = |c: u8| {
+        if hex {
+            c.is_ascii_hexdigit()
+        } else {
+            c.is_ascii_digit()
+        }
+    };
+    let mantissa_start = i;
+    let mut point = false;
+    while i < bytes.len() && (is_digit(bytes[i]) || bytes[i] == b'.' && !point) {
+        point |= bytes[i] == b'.';
+        i += 1;
+    }
+    if !bytes[mantissa_start..i].iter().any(|&c| is_digit(c)) {
+        return "";
+    }
+    // The exponent only belongs to the number if digits follow its marker.
+    let exp_marker: &[u8] = if hex { b"pP" } else { b"eE" };
+    if i < bytes.len() && exp_marker.contains(&bytes[i]) {
+        let mut j = i + 1;
+        if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+            j += 1;
+        }
+        let exp_start = j;
+        while j < bytes.len() && bytes[j].is_ascii_digit() {
+            j += 1;
+        }
+        if j > exp_start {
+            i = j;
+        }
     }
+    &s[..i]
 }
 
-impl fmt::LowerExp for f128 {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        // FIXME: use actual format string and do not
-        // allocate a string
-        write!(f, "{}", self.to_string_fmt("%.36Qe").unwrap())
+impl Default for f128 {
+    #[inline]
+    fn default() -> f128 {
+        f128::ZERO
     }
 }
 
@@ -303,62 +471,64 @@ impl One for f128 {
     }
 }
 
+/// Integers are rounded toward zero, and are `None` for NaN and values that do not fit, which
+/// raises the invalid exception.
 impl ToPrimitive for f128 {
     #[inline]
     fn to_i64(&self) -> Option<i64> {
-        Some(unsafe { f128_to_i64(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_u64(&self) -> Option<u64> {
-        Some(unsafe { f128_to_u64(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_isize(&self) -> Option<isize> {
-        Some(unsafe { f128_to_i64(*self) as isize })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_i8(&self) -> Option<i8> {
-        Some(unsafe { f128_to_i8(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_i16(&self) -> Option<i16> {
-        Some(unsafe { f128_to_i16(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_i32(&self) -> Option<i32> {
-        Some(unsafe { f128_to_i32(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_usize(&self) -> Option<usize> {
-        Some(unsafe { f128_to_u64(*self) as usize })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_u8(&self) -> Option<u8> {
-        Some(unsafe { f128_to_u8(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_u16(&self) -> Option<u16> {
-        Some(unsafe { f128_to_u16(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_u32(&self) -> Option<u32> {
-        Some(unsafe { f128_to_u32(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_f32(&self) -> Option<f32> {
-        Some(unsafe { f128_to_f32(*self) })
+        Some(rounding::f128_to_f32(*self))
     }
     #[inline]
     fn to_f64(&self) -> Option<f64> {
-        Some(unsafe { f128_to_f64(*self) })
+        Some(rounding::f128_to_f64(*self))
     }
     #[inline]
     fn to_i128(&self) -> Option<i128> {
-        Some(unsafe { f128_to_i128(*self) })
+        convert::truncated(*self)
     }
     #[inline]
     fn to_u128(&self) -> Option<u128> {
-        Some(unsafe { f128_to_u128(*self) })
+        convert::truncated(*self)
     }
 }
 
@@ -413,26 +583,87 @@ impl FromPrimitive for f128 {
     }
     #[inline]
     fn from_u128(n: u128) -> Option<Self> {
-        Some(unsafe { u128_to_f128(mem::transmute(n)) })
+        Some(rounding::u128_to_f128(n))
     }
     #[inline]
     fn from_i128(n: i128) -> Option<Self> {
-        Some(unsafe { i128_to_f128(mem::transmute(n)) })
+        Some(rounding::i128_to_f128(n))
     }
 }
 
 impl Num for f128 {
-    type FromStrRadixErr = ();
+    type FromStrRadixErr = ParseF128Error;
+
+    /// Parses a number in any radix from 2 to 36, correctly rounded in the current rounding
+    /// mode. An `e` exponent scales by a power of the radix and is accepted up to radix 14, a `p`
+    /// exponent scales by a power of two and is accepted up to radix 25; both are decimal.
+    ///
+    /// # Panics
+    ///
+    /// If `radix` is not in the range `2..=36`.
     #[inline]
-    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ()> {
-        unimplemented!()
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseF128Error> {
+        parse::from_str_radix(s, radix)
     }
 }
 
-impl NumCast for f128 {
+/// Parses a decimal number such as `-1.5e-3`, or a hexadecimal one with a `0x` prefix such as
+/// `0x1.8p-3`, correctly rounded in the current rounding mode. `inf`, `infinity` and `nan` are
+/// accepted in any case.
+///
+/// Unlike `f128::parse`, the whole string must be a number, and numbers that are too large or too
+/// small to be represented are errors rather than infinity or zero.
+impl FromStr for f128 {
+    type Err = ParseF128Error;
+
     #[inline]
+    fn from_str(s: &str) -> Result<f128, ParseF128Error> {
+        parse::from_str(s)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for f128 {
+    type Error = ParseF128Error;
+
+    #[inline]
+    fn try_from(s: &'a str) -> Result<f128, ParseF128Error> {
+        parse::from_str(s)
+    }
+}
+
+/// Converts integers of up to 128 bits through `i128` or `u128`, rounding only those wider than
+/// 113 bits, and everything else through `f64` (or `f32` where that is all there is). Only `None`
+/// from all of those gives `None`. An `f128` is returned unchanged.
+impl NumCast for f128 {
     fn from<T: ToPrimitive>(n: T) -> Option<Self> {
-        f128::from_f64(n.to_f64().unwrap())
+        // `T` need not be `'static`, which rules out `Any`, but an `f128` source still has to be
+        // recognised: `ToPrimitive` has no way to hand over all of its bits.
+        if mem::size_of::<T>() == mem::size_of::<f128>()
+            && std::any::type_name::<T>() == std::any::type_name::<f128>()
+        {
+            return Some(unsafe { mem::transmute_copy(&n) });
+        }
+        let float = n.to_f64();
+        // Floats convert to integers by rounding toward zero, so only a source that is exactly
+        // the integer, as far as its `f64` can tell, takes the integer path; zero keeps its sign
+        // on the float path. From 2^53 on, `f64` cannot tell integers apart from the values in
+        // between, and truncating is closer to the source than rounding to `f64` anyway.
+        let takes = |i: f64| {
+            float.map_or(true, |f| f != 0.0 && (f == i || f.abs() >= 9007199254740992.0))
+        };
+        if let Some(i) = n.to_i128() {
+            if takes(i as f64) {
+                return f128::from_i128(i);
+            }
+        } else if let Some(u) = n.to_u128() {
+            if takes(u as f64) {
+                return f128::from_u128(u);
+            }
+        }
+        match float {
+            Some(f) => f128::from_f64(f),
+            None => n.to_f32().and_then(f128::from_f32),
+        }
     }
 }
 
@@ -472,54 +703,64 @@ impl Signed for f128 {
     }
 }
 
+/// Every constant is the `f128` nearest to its exact value.
 impl FloatConst for f128 {
     fn E() -> Self {
         f128::E
     }
     fn FRAC_1_PI() -> Self {
-        f128::PI.inv()
+        f128::FRAC_1_PI
     }
     fn FRAC_1_SQRT_2() -> Self {
-        unimplemented!();
+        f128::FRAC_1_SQRT_2
     }
     fn FRAC_2_PI() -> Self {
-        f128::new(2.0) / f128::PI
+        f128::FRAC_2_PI
     }
     fn FRAC_2_SQRT_PI() -> Self {
-        unimplemented!();
+        f128::FRAC_2_SQRT_PI
     }
     fn FRAC_PI_2() -> Self {
-        f128::PI / f128::new(2.0)
+        f128::FRAC_PI_2
     }
     fn FRAC_PI_3() -> Self {
-        f128::PI / f128::new(3.0)
+        f128::FRAC_PI_3
     }
     fn FRAC_PI_4() -> Self {
-        f128::PI / f128::new(4.0)
+        f128::FRAC_PI_4
     }
     fn FRAC_PI_6() -> Self {
-        f128::PI / f128::new(6.0)
+        f128::FRAC_PI_6
     }
     fn FRAC_PI_8() -> Self {
-        f128::PI / f128::new(8.0)
+        f128::FRAC_PI_8
     }
     fn LN_10() -> Self {
-        unimplemented!();
+        f128::LN_10
     }
     fn LN_2() -> Self {
-        unimplemented!();
+        f128::LN_2
     }
     fn LOG10_E() -> Self {
-        unimplemented!();
+        f128::LOG10_E
     }
     fn LOG2_E() -> Self {
-        unimplemented!();
+        f128::LOG2_E
     }
     fn PI() -> Self {
         f128::PI
     }
     fn SQRT_2() -> Self {
-        unimplemented!();
+        f128::SQRT_2
+    }
+    fn TAU() -> Self {
+        f128::TAU
+    }
+    fn LOG10_2() -> Self {
+        f128::LOG10_2
+    }
+    fn LOG2_10() -> Self {
+        f128::LOG2_10
     }
 }
 
@@ -531,12 +772,12 @@ impl num_traits::float::FloatCore for f128 {
 
     #[inline]
     fn to_degrees(self) -> Self {
-        unimplemented!()
+        Float::to_degrees(self)
     }
 
     #[inline]
     fn to_radians(self) -> Self {
-        unimplemented!()
+        Float::to_radians(self)
     }
 
     #[inline]
@@ -672,7 +913,7 @@ impl num_traits::float::FloatCore for f128 {
 
     #[inline]
     fn integer_decode(self) -> (u64, i16, i8) {
-        unimplemented!("This function cannot be accurately implemented with num v0.2.6 - the mantissa type needs to be upped to u128.")
+        Float::integer_decode(self)
     }
 }
 
@@ -726,14 +967,12 @@ impl Float for f128 {
     fn is_infinite(self) -> bool {
         // It's fine to compare the bits here since there is only 1 bit pattern that is inf, and one
         // that is -inf.
-        let res = (self.inner_as_u128() & 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFu128);
-        res == f128::EXPONENT_BITS.inner_as_u128()
+        self.to_bits() & !(1 << 127) == f128::EXPONENT_BITS.to_bits()
     }
 
     #[inline]
     fn is_nan(self) -> bool {
-        (self.inner_as_u128() & 0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFu128)
-            > f128::EXPONENT_BITS.inner_as_u128()
+        self.to_bits() & !(1 << 127) > f128::EXPONENT_BITS.to_bits()
     }
 
     #[inline]
@@ -746,6 +985,7 @@ impl Float for f128 {
     fn classify(self) -> FpCategory {
         let x = (self.is_normal(), self.is_finite(), self.is_nan());
         match x {
+            _ if self.exp_bits() == 0 && self.fract_bits() == 0 => FpCategory::Zero,
             (true, true, false) => FpCategory::Normal,
             (false, true, false) => FpCategory::Subnormal,
             (_, _, true) => FpCategory::Nan,
@@ -776,8 +1016,7 @@ impl Float for f128 {
 
     #[inline]
     fn fract(self) -> Self {
-        let mut x: c_int = 0;
-        unsafe { frexpq_f(self, &mut x) }
+        self - self.trunc()
     }
 
     #[cfg(target_endian = "big")]
@@ -795,14 +1034,12 @@ impl Float for f128 {
 
     #[inline]
     fn signum(self) -> Self {
-        if self == Self::NAN {
-            return self;
+        if self.is_nan() {
+            self
+        } else if self.is_sign_positive() {
+            Self::ONE
         } else {
-            if self.is_sign_positive() {
-                Self::ONE
-            } else {
-                -Self::ONE
-            }
+            -Self::ONE
         }
     }
 
@@ -810,8 +1047,8 @@ impl Float for f128 {
     #[inline]
     fn is_sign_negative(self) -> bool {
         match self.0[0] & 0x80 {
-            0 => true,
-            0x80 => false,
+            0 => false,
+            0x80 => true,
             _ => unreachable!(),
         }
     }
@@ -856,22 +1093,11 @@ impl Float for f128 {
         f128::ONE / self
     }
 
+    /// `self` to the power of `n`, computed by `powq` so that it is rounded once rather than after
+    /// every multiplication.
     #[inline]
     fn powi(self, n: i32) -> f128 {
-        let mut i = self.clone();
-        if n == 0 {
-            return f128::ONE;
-        };
-        if (n < 0) {
-            for _ in n as i64 - 1..0 {
-                i /= self;
-            }
-        } else {
-            for _ in 1..n {
-                i *= self;
-            }
-        }
-        i
+        unsafe { powq_f(self, i32_to_f128(n)) }
     }
 
     #[inline]
@@ -879,9 +1105,15 @@ impl Float for f128 {
         unsafe { powq_f(self, n) }
     }
 
+    /// The correctly rounded square root in the current rounding mode. libquadmath's `sqrtq` is
+    /// not always correctly rounded, so only the `f128` primitive's is used in its place.
     #[inline]
     fn sqrt(self) -> f128 {
-        unsafe { sqrtq_f(self) }
+        match rounding::soft_mode() {
+            #[cfg(feature = "native-f128")]
+            None => unsafe { sqrtq_f(self) },
+            _ => soft::sqrt(self, rounding::RoundingMode::current()),
+        }
     }
 
     #[inline]
@@ -891,8 +1123,7 @@ impl Float for f128 {
 
     #[inline]
     fn exp2(self) -> f128 {
-        // TODO: Change this to a constant two
-        (f128::ONE * f128::from_u8(2).unwrap()).powf(self)
+        unsafe { exp2q_f(self) }
     }
 
     #[inline]
@@ -918,27 +1149,32 @@ impl Float for f128 {
         unsafe { log10q_f(self) }
     }
 
+    #[inline]
+    fn to_degrees(self) -> f128 {
+        self * f128!(57.295779513082320876798154814105170332405472466564)
+    }
+
+    #[inline]
+    fn to_radians(self) -> f128 {
+        self * f128!(0.017453292519943295769236907684886127134428718885417)
+    }
+
+    /// The larger of `self` and `other` like C's `fmaxq`: if one of them is NaN, the other.
     #[inline]
     fn max(self, other: f128) -> f128 {
-        unsafe {
-            let a = mem::transmute::<f128, i128>(self);
-            let b = mem::transmute::<f128, i128>(other);
-            mem::transmute::<i128, f128>(if a > b { a } else { b })
-        }
+        unsafe { fmaxq_f(self, other) }
     }
 
+    /// The smaller of `self` and `other` like C's `fminq`: if one of them is NaN, the other.
     #[inline]
     fn min(self, other: f128) -> f128 {
-        unsafe {
-            let a = mem::transmute::<f128, i128>(self);
-            let b = mem::transmute::<f128, i128>(other);
-            mem::transmute::<i128, f128>(if a > b { b } else { a })
-        }
+        unsafe { fminq_f(self, other) }
     }
 
+    /// The positive difference `max(self - other, 0)`, like C's `fdimq`.
     #[inline]
     fn abs_sub(self, other: f128) -> f128 {
-        (self - other).abs()
+        self.fdim(other)
     }
 
     #[inline]
@@ -988,7 +1224,9 @@ impl Float for f128 {
 
     #[inline]
     fn sin_cos(self) -> (f128, f128) {
-        (self.sin(), self.cos())
+        let (mut sin, mut cos) = (f128::ZERO, f128::ZERO);
+        unsafe { sincosq_f(self, &mut sin, &mut cos) };
+        (sin, cos)
     }
 
     #[inline]
@@ -1031,8 +1269,19 @@ impl Float for f128 {
         unsafe { atanhq_f(self) }
     }
 
+    /// `decode` squeezed into the narrower types of num-traits: the significand keeps its 64 most
+    /// significant bits and the rest is truncated, so `mantissa * 2^exponent` is within a relative
+    /// `2^-63` of `self` and exact whenever the significand has at most 64 bits. Use `decode` for
+    /// the lossless version.
     #[inline]
     fn integer_decode(self) -> (u64, i16, i8) {
-        unimplemented!("This function cannot be accurately implemented with num v0.2.6 - the mantissa type needs to be upped to u128.")
+        let (negative, mut exponent, mut significand) = self.decode();
+        let width = 128 - significand.leading_zeros() as i32;
+        if width > 64 {
+            significand >>= width - 64;
+            exponent += width - 64;
+        }
+        let sign = if negative { -1 } else { 1 };
+        (significand as u64, exponent as i16, sign)
     }
 }
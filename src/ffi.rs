@@ -1,18 +1,48 @@
 use libc::c_int;
+use libc::c_long;
 use libc::c_longlong;
 use f128_t::f128;
 
+#[cfg(feature = "native-f128")]
+pub use native::{
+    acoshq_f, acosq_f, asinhq_f, asinq_f, atan2q_f, atanhq_f, atanq_f, cbrtq_f, ceilq_f, coshq_f,
+    cosq_f, eqq, expm1q_f, expq_f, f128_add, f128_div, f128_mul, f128_sub, f128_to_f32,
+    f128_to_f64, f128_to_i128, f128_to_i16, f128_to_i32, f128_to_i64, f128_to_i8, f128_to_isize,
+    f128_to_u128, f128_to_u16, f128_to_u32, f128_to_u64, f128_to_u8, f128_to_usize, f32_to_f128,
+    f64_to_f128, floorq_f, fmaq_f, fmodq_f, gteq, gtq, hypotq_f, i128_to_f128, i16_to_f128,
+    i32_to_f128, i64_to_f128, i8_to_f128, isize_to_f128, log10q_f, log1pq_f, log2q_f, logq_f, lteq,
+    ltq, neqq, powq_f, roundq_f, sinhq_f, sinq_f, sqrtq_f, tanhq_f, tanq_f, truncq_f, u128_to_f128,
+    u16_to_f128, u32_to_f128, u64_to_f128, u8_to_f128, usize_to_f128,
+};
+
+#[cfg(feature = "soft-float")]
+pub use soft::{
+    eqq, f128_add, f128_div, f128_mul, f128_sub, f128_to_f32, f128_to_f64, f128_to_i128,
+    f128_to_i16, f128_to_i32, f128_to_i64, f128_to_i8, f128_to_isize, f128_to_u128, f128_to_u16,
+    f128_to_u32, f128_to_u64, f128_to_u8, f128_to_usize, f32_to_f128, f64_to_f128, gteq, gtq,
+    i128_to_f128, i16_to_f128, i32_to_f128, i64_to_f128, i8_to_f128, isize_to_f128, lteq, ltq,
+    neqq, u128_to_f128, u16_to_f128, u32_to_f128, u64_to_f128, u8_to_f128, usize_to_f128,
+};
+
+#[cfg(feature = "soft-float")]
+pub use soft::math::{
+    acoshq_f, acosq_f, asinhq_f, asinq_f, atan2q_f, atanhq_f, atanq_f, cbrtq_f, ceilq_f,
+    copysignq_f, coshq_f, cosq_f, exp2q_f, expm1q_f, expq_f, fdimq_f, floorq_f, fmaq_f, fmaxq_f,
+    fminq_f, fmodq_f, frexpq_f, hypotq_f, ilogbq_f, ldexpq_f, llrintq_f, llroundq_f, log10q_f,
+    log1pq_f, log2q_f, logbq_f, logq_f, modfq_f, nearbyintq_f, nextafterq_f, powq_f, remainderq_f,
+    remquoq_f, rintq_f, roundq_f, scalblnq_f, scalbnq_f, sincosq_f, sinhq_f, sinq_f, tanhq_f,
+    tanq_f, truncq_f,
+};
+
+// The shims the `soft-float` and `native-f128` features replace with the pure-Rust versions in
+// `soft.rs` and `native.rs`.
+#[cfg(not(any(feature = "soft-float", feature = "native-f128")))]
 #[link(name = "f128", kind = "static")]
 extern "C" {
     pub fn f128_add(x: f128, y: f128) -> f128;
     pub fn f128_sub(x: f128, y: f128) -> f128;
     pub fn f128_div(x: f128, y: f128) -> f128;
     pub fn f128_mul(x: f128, y: f128) -> f128;
-    pub fn f128_modulo(x: f128, y: f128) -> f128;
-
-    pub fn qtostr(s: *mut u8, size: usize, fmt: *const i8, arg: f128) -> c_int;
-
-    pub fn strtoflt128_f(c: *const i8) -> f128;
 
     pub fn usize_to_f128	(x: usize) -> f128;
     pub fn f128_to_usize	(x: f128) -> usize;
@@ -43,8 +73,19 @@ extern "C" {
     pub fn f64_to_f128	(x: f64) -> f128;
     pub fn f128_to_f64	(x: f128) -> f64;
 
+    pub fn gtq(lhs: f128, rhs: f128) -> u8;
+    pub fn gteq(lhs: f128, rhs: f128) -> u8;
+    pub fn eqq(lhs: f128, rhs: f128) -> u8;
+    pub fn neqq(lhs: f128, rhs: f128) -> u8;
+    pub fn ltq(lhs: f128, rhs: f128) -> u8;
+    pub fn lteq(lhs: f128, rhs: f128) -> u8;
+}
+
+// The math functions `native-f128` implements on top of the primitive's methods.
+#[cfg(not(any(feature = "soft-float", feature = "native-f128")))]
+#[link(name = "f128", kind = "static")]
+extern "C" {
     pub fn acosq_f(x: f128) -> f128;
-    pub fn acosn_f (a: f128) -> f128;
     pub fn acoshq_f (a: f128) -> f128;
     pub fn asinq_f (a: f128) -> f128;
     pub fn asinhq_f (a: f128) -> f128;
@@ -53,23 +94,49 @@ extern "C" {
     pub fn atan2q_f (a: f128, b: f128) -> f128;
     pub fn cbrtq_f (a: f128) -> f128;
     pub fn ceilq_f (a: f128) -> f128;
-    pub fn copysignq_f (a: f128, b: f128) -> f128;
     pub fn coshq_f (a: f128) -> f128;
     pub fn cosq_f (a: f128) -> f128;
-    pub fn erfq_f (a: f128) -> f128;
-    pub fn erfcq_f (a: f128) -> f128;
     pub fn expq_f (a: f128) -> f128;
     pub fn expm1q_f (a: f128) -> f128;
+    pub fn floorq_f (a: f128) -> f128;
+    pub fn fmaq_f (a: f128, b: f128, c: f128) -> f128;
+    pub fn fmodq_f (a: f128, b: f128) -> f128;
+    pub fn hypotq_f (a: f128, b: f128) -> f128;
+    pub fn logq_f (a: f128) -> f128;
+    pub fn log10q_f (a: f128) -> f128;
+    pub fn log2q_f (a: f128) -> f128;
+    pub fn log1pq_f (a: f128) -> f128;
+    pub fn powq_f (a: f128, b: f128) -> f128;
+    pub fn roundq_f (a: f128) -> f128;
+    pub fn sinhq_f (a: f128) -> f128;
+    pub fn sinq_f (a: f128) -> f128;
+    pub fn sqrtq_f (a: f128) -> f128;
+    pub fn tanq_f (a: f128) -> f128;
+    pub fn tanhq_f (a: f128) -> f128;
+    pub fn truncq_f (a: f128) -> f128;
+}
+
+// The rest of `src/f128.c`, which `soft-float` leaves out of the build along with libquadmath.
+#[cfg(not(feature = "soft-float"))]
+#[link(name = "f128", kind = "static")]
+extern "C" {
+    pub fn f128_modulo(x: f128, y: f128) -> f128;
+
+    pub fn qtostr(s: *mut u8, size: usize, fmt: *const i8, arg: f128) -> c_int;
+
+    pub fn strtoflt128_f(c: *const i8) -> f128;
+
+    pub fn acosn_f (a: f128) -> f128;
+    pub fn copysignq_f (a: f128, b: f128) -> f128;
+    pub fn erfq_f (a: f128) -> f128;
+    pub fn erfcq_f (a: f128) -> f128;
+    pub fn exp2q_f (a: f128) -> f128;
     pub fn fabsq_f (a: f128) -> f128;
     pub fn fdimq_f (a: f128, b: f128) -> f128;
     pub fn finiteq_f (a: f128) -> c_int;
-    pub fn floorq_f (a: f128) -> f128;
-    pub fn fmaq_f (a: f128, b: f128, c: f128) -> f128;
     pub fn fmaxq_f (a: f128, b: f128) -> f128;
     pub fn fminq_f (a: f128, b: f128) -> f128;
-    pub fn fmodq_f (a: f128, b: f128) -> f128;
     pub fn frexpq_f (a: f128, b: *mut c_int) -> f128;
-    pub fn hypotq_f (a: f128, b: f128) -> f128;
     pub fn isinfq_f (a: f128) -> c_int;
     pub fn ilogbq_f (a: f128) -> c_int;
     pub fn isnanq_f (a: f128) -> c_int;
@@ -81,41 +148,22 @@ extern "C" {
     pub fn llrintq_f (a: f128) -> c_longlong;
     pub fn llroundq_f (a: f128) -> c_longlong;
     pub fn logbq_f (a: f128) -> f128;
-    pub fn logq_f (a: f128) -> f128;
-    pub fn log10q_f (a: f128) -> f128;
-    pub fn log2q_f (a: f128) -> f128;
-    pub fn log1pq_f (a: f128) -> f128;
-    pub fn lrintq_f (a: f128) -> c_longlong;
-    pub fn lroundq_f (a: f128) -> c_longlong;
+    pub fn lrintq_f (a: f128) -> c_long;
+    pub fn lroundq_f (a: f128) -> c_long;
     pub fn modfq_f (a: f128, b: *mut f128) -> f128;
     pub fn nanq_f (a: *mut u8) -> f128;
     pub fn nearbyintq_f (a: f128) -> f128;
     pub fn nextafterq_f (a: f128, b: f128) -> f128;
-    pub fn powq_f (a: f128, b: f128) -> f128;
     pub fn remainderq_f (a: f128, b: f128) -> f128;
     pub fn remquoq_f (a: f128, b: f128, c: *mut c_int) -> f128;
     pub fn rintq_f (a: f128) -> f128;
-    pub fn roundq_f (a: f128) -> f128;
-    pub fn scalblnq_f (a: f128, b: c_int) -> f128;
+    pub fn scalblnq_f (a: f128, b: c_long) -> f128;
     pub fn scalbnq_f (a: f128, b: c_int) -> f128;
     pub fn signbitq_f (a: f128) -> c_int;
-    pub fn sincosq_f (a: f128, b: *mut f128, c: *mut f128) -> f128;
-    pub fn sinhq_f (a: f128) -> f128;
-    pub fn sinq_f (a: f128) -> f128;
-    pub fn sqrtq_f (a: f128) -> f128;
-    pub fn tanq_f (a: f128) -> f128;
-    pub fn tanhq_f (a: f128) -> f128;
+    pub fn sincosq_f (a: f128, b: *mut f128, c: *mut f128);
     pub fn tgammaq_f (a: f128) -> f128;
-    pub fn truncq_f (a: f128) -> f128;
     pub fn y0q_f (a: f128) -> f128;
     pub fn y1q_f (a: f128) -> f128;
     pub fn ynq_f (a: c_int, b: f128) -> f128;
 
-    pub fn gtq(lhs: f128, rhs: f128) -> u8;
-    pub fn gteq(lhs: f128, rhs: f128) -> u8;
-    pub fn eqq(lhs: f128, rhs: f128) -> u8;
-    pub fn neqq(lhs: f128, rhs: f128) -> u8;
-    pub fn ltq(lhs: f128, rhs: f128) -> u8;
-    pub fn lteq(lhs: f128, rhs: f128) -> u8;
-
 }
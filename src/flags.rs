@@ -0,0 +1,201 @@
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// A set of IEEE 754 exception flags.
+///
+/// Flags are sticky: once an operation raises one it stays raised until it is cleared. They are
+/// only recorded on threads that asked for them with `with_flags` or a `FlagsGuard`, since that
+/// routes `f128` arithmetic through the pure-Rust implementation, which is the only one that
+/// reports them.
+///
+/// The operations that raise flags are `+`, `-`, `*`, `/`, `sqrt`, the conversions that can
+/// round (from `u128`/`i128` and to `f32`/`f64`), comparisons involving NaN, and `f128::parse`,
+/// which reports overflow to infinity and underflow to zero.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FpFlags(u8);
+
+impl FpFlags {
+    /// An operation had no usefully definable result, e.g. `0 / 0`, `inf - inf`, `sqrt(-1)`, an
+    /// ordered comparison with NaN or any operation on a signalling NaN.
+    pub const INVALID: FpFlags = FpFlags(1);
+    /// A finite non-zero number was divided by zero.
+    pub const DIV_BY_ZERO: FpFlags = FpFlags(2);
+    /// A rounded result was too large to be represented.
+    pub const OVERFLOW: FpFlags = FpFlags(4);
+    /// A result was tiny (below the smallest normal number before rounding) and inexact.
+    pub const UNDERFLOW: FpFlags = FpFlags(8);
+    /// A result had to be rounded.
+    pub const INEXACT: FpFlags = FpFlags(16);
+
+    #[inline]
+    pub const fn empty() -> FpFlags {
+        FpFlags(0)
+    }
+
+    #[inline]
+    pub const fn all() -> FpFlags {
+        FpFlags(31)
+    }
+
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Whether every flag in `other` is also in `self`.
+    #[inline]
+    pub const fn contains(self, other: FpFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any flag in `other` is also in `self`.
+    #[inline]
+    pub const fn intersects(self, other: FpFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// The flags raised on the current thread so far.
+    #[inline]
+    pub fn current() -> FpFlags {
+        RAISED.with(|r| r.get())
+    }
+
+    /// Whether any of `flags` has been raised on the current thread.
+    #[inline]
+    pub fn test(flags: FpFlags) -> bool {
+        FpFlags::current().intersects(flags)
+    }
+
+    /// Lowers all flags on the current thread.
+    #[inline]
+    pub fn clear() {
+        RAISED.with(|r| r.set(FpFlags::empty()));
+    }
+
+    /// Returns the flags raised on the current thread and lowers them.
+    #[inline]
+    pub fn take() -> FpFlags {
+        RAISED.with(|r| r.replace(FpFlags::empty()))
+    }
+}
+
+impl BitOr for FpFlags {
+    type Output = FpFlags;
+
+    #[inline]
+    fn bitor(self, other: FpFlags) -> FpFlags {
+        FpFlags(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for FpFlags {
+    #[inline]
+    fn bitor_assign(&mut self, other: FpFlags) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitAnd for FpFlags {
+    type Output = FpFlags;
+
+    #[inline]
+    fn bitand(self, other: FpFlags) -> FpFlags {
+        FpFlags(self.0 & other.0)
+    }
+}
+
+impl fmt::Debug for FpFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const NAMES: [(FpFlags, &str); 5] = [
+            (FpFlags::INVALID, "INVALID"),
+            (FpFlags::DIV_BY_ZERO, "DIV_BY_ZERO"),
+            (FpFlags::OVERFLOW, "OVERFLOW"),
+            (FpFlags::UNDERFLOW, "UNDERFLOW"),
+            (FpFlags::INEXACT, "INEXACT"),
+        ];
+        if self.is_empty() {
+            return f.write_str("FpFlags(empty)");
+        }
+        f.write_str("FpFlags(")?;
+        let mut first = true;
+        for &(flag, name) in NAMES.iter().filter(|&&(flag, _)| self.contains(flag)) {
+            if !first {
+                f.write_str(" | ")?;
+            }
+            f.write_str(name)?;
+            first = false;
+        }
+        f.write_str(")")
+    }
+}
+
+thread_local! {
+    static RAISED: Cell<FpFlags> = const { Cell::new(FpFlags(0)) };
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the current thread records exception flags.
+#[inline]
+pub(crate) fn recording() -> bool {
+    RECORDING.with(|r| r.get())
+}
+
+/// Raises `flags` on the current thread if it records them.
+#[inline]
+pub(crate) fn raise(flags: FpFlags) {
+    if !flags.is_empty() && recording() {
+        RAISED.with(|r| r.set(r.get() | flags));
+    }
+}
+
+/// Records exception flags on the current thread until it is dropped.
+///
+/// Like `RoundingGuard`, guards must be dropped in the reverse order they were created in.
+#[must_use = "flags stop being recorded as soon as the guard is dropped"]
+pub struct FlagsGuard {
+    previous: bool,
+    // Flags set aside by `with_flags`, raised again when the guard is dropped.
+    outer: FpFlags,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl FlagsGuard {
+    pub fn new() -> FlagsGuard {
+        FlagsGuard::setting_aside(FpFlags::empty())
+    }
+
+    fn setting_aside(outer: FpFlags) -> FlagsGuard {
+        let previous = RECORDING.with(|r| r.replace(true));
+        FlagsGuard {
+            previous,
+            outer,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for FlagsGuard {
+    fn drop(&mut self) {
+        RAISED.with(|r| r.set(r.get() | self.outer));
+        RECORDING.with(|r| r.set(self.previous));
+    }
+}
+
+/// Runs `f` while recording exception flags and returns its result together with the flags it
+/// raised.
+///
+/// Flags raised before the call are kept, and the ones `f` raised are added to them afterwards,
+/// so calls can be nested. Both happen even if `f` panics, as does restoring whether flags were
+/// being recorded.
+pub fn with_flags<T, F: FnOnce() -> T>(f: F) -> (T, FpFlags) {
+    let _guard = FlagsGuard::setting_aside(FpFlags::take());
+    let result = f();
+    (result, FpFlags::current())
+}
@@ -0,0 +1,58 @@
+//! Hashing of `f128`, either consistently with `==` or by the raw bits.
+
+use f128_t::f128;
+use num_traits::{Float, Zero};
+use std::hash::{Hash, Hasher};
+
+/// Hashes consistently with `PartialEq`: `+0` and `-0` hash the same since they compare equal,
+/// and so do all NaNs.
+///
+/// `f128` is not `Eq` since NaN never equals itself, so it cannot key a `HashMap` or `HashSet`
+/// directly; this hash serves types that hold `f128`s and define their own equality through
+/// `==`. As keys, use `BitHash` or `TotalF128`, which tell zeros and NaN payloads apart.
+impl Hash for f128 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let canonical = if self.is_zero() {
+            0
+        } else if self.is_nan() {
+            f128::NAN.to_bits()
+        } else {
+            self.to_bits()
+        };
+        state.write_u128(canonical)
+    }
+}
+
+/// An `f128` compared and hashed by its bits, so that `-0` differs from `+0`, NaNs with
+/// different payloads differ, and each NaN equals itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BitHash(pub f128);
+
+impl PartialEq for BitHash {
+    #[inline]
+    fn eq(&self, other: &BitHash) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for BitHash {}
+
+impl Hash for BitHash {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.0.to_bits())
+    }
+}
+
+impl From<f128> for BitHash {
+    #[inline]
+    fn from(x: f128) -> BitHash {
+        BitHash(x)
+    }
+}
+
+impl From<BitHash> for f128 {
+    #[inline]
+    fn from(x: BitHash) -> f128 {
+        x.0
+    }
+}
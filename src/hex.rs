@@ -0,0 +1,121 @@
+//! Hexadecimal floating-point literals, as written by C's `%a`: `0x1.921fb54442d18469898cc51701b8p+1`.
+
+use f128_t::f128;
+use num_traits::Float;
+use parse::{self, ParseF128Error};
+use std::fmt;
+
+/// Writes the magnitude of the finite `x` as `h.hhhp±d`, normalised so that the digit before the
+/// point is 1 (0 for zero). Without a precision the fraction has as few digits as are needed to
+/// be exact; with one it has exactly that many, rounded to nearest, ties to even.
+fn hex_digits(x: f128, precision: Option<usize>, upper: bool) -> String {
+    let (_, mut exp, mut sig) = x.decode();
+    if sig == 0 {
+        exp = 0;
+    } else {
+        let shift = sig.leading_zeros() - 15;
+        sig <<= shift;
+        exp += 112 - shift as i32;
+    }
+
+    // `sig` holds the leading digit followed by `digits` hexadecimal fraction digits.
+    let mut digits = 28;
+    if let Some(p) = precision.filter(|&p| p < 28) {
+        let drop = 4 * (28 - p) as u32;
+        let rest = sig & ((1 << drop) - 1);
+        let half = 1 << (drop - 1);
+        sig >>= drop;
+        if rest > half || (rest == half && sig & 1 != 0) {
+            sig += 1;
+            if sig >> (4 * p as u32 + 1) != 0 {
+                sig >>= 1;
+                exp += 1;
+            }
+        }
+        digits = p;
+    }
+    if precision.is_none() {
+        while digits > 0 && sig & 0xf == 0 {
+            sig >>= 4;
+            digits -= 1;
+        }
+    }
+
+    let table = if upper {
+        b"0123456789ABCDEF"
+    } else {
+        b"0123456789abcdef"
+    };
+    let mut s = String::with_capacity(40);
+    s.push((b'0' + (sig >> (4 * digits)) as u8) as char);
+    let width = precision.unwrap_or(digits);
+    if width > 0 {
+        s.push('.');
+        for i in (0..digits).rev() {
+            s.push(table[(sig >> (4 * i) & 0xf) as usize] as char);
+        }
+        s.extend(::std::iter::repeat('0').take(width - digits));
+    }
+    s.push(if upper { 'P' } else { 'p' });
+    if exp >= 0 {
+        s.push('+');
+    }
+    s.push_str(&exp.to_string());
+    s
+}
+
+fn fmt_hex(x: f128, f: &mut fmt::Formatter, upper: bool) -> fmt::Result {
+    if !x.is_finite() {
+        let s = match (x.is_nan(), upper) {
+            (true, false) => "nan",
+            (true, true) => "NAN",
+            (false, false) => "inf",
+            (false, true) => "INF",
+        };
+        return if x.is_sign_negative() && !x.is_nan() {
+            f.pad(&format!("-{}", s))
+        } else {
+            f.pad(s)
+        };
+    }
+    let digits = hex_digits(x, f.precision(), upper);
+    f.pad_integral(!x.is_sign_negative(), "0x", &digits)
+}
+
+/// Formats finite numbers as `1.921fb54442d18469898cc51701b8p+1`, with as many fraction digits
+/// as it takes to be exact, or as many as the precision asks for. The `#` flag adds a `0x` prefix
+/// and the other flags work as they do for integers. Infinities and NaN are written as `inf` and
+/// `nan`.
+impl fmt::LowerHex for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(*self, f, false)
+    }
+}
+
+/// Like `LowerHex`, with upper-case digits, `P` and `INF`/`NAN`.
+impl fmt::UpperHex for f128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_hex(*self, f, true)
+    }
+}
+
+impl f128 {
+    /// Formats `self` as a C99 hexadecimal floating-point literal such as
+    /// `0x1.921fb54442d18469898cc51701b8p+1`.
+    ///
+    /// `f128::from_hex_str` turns the result back into exactly the same value, signed zeros
+    /// included. NaN is written as `nan`, without its payload.
+    pub fn to_hex_string(&self) -> String {
+        format!("{:#x}", self)
+    }
+
+    /// Parses a hexadecimal floating-point literal such as `0x1.921fb54442d18469898cc51701b8p+1`
+    /// or `-0x1p-16494`: an optional sign, an optional `0x` prefix, hexadecimal digits with an
+    /// optional point and an optional binary exponent written in decimal after `p`. `inf`,
+    /// `infinity` and `nan` are accepted too.
+    ///
+    /// Literals with more digits than fit are rounded correctly in the current rounding mode.
+    pub fn from_hex_str(s: &str) -> Result<f128, ParseF128Error> {
+        parse::from_hex_str(s)
+    }
+}
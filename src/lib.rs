@@ -1,14 +1,54 @@
+//! The IEEE 754 binary128 type `f128`, backed by GCC's libquadmath.
+//!
+//! # Features
+//!
+//! - `soft-float` replaces the C shims and libquadmath with pure Rust, so the crate builds without
+//!   GCC. The math functions round to nearest whatever the rounding mode, and
+//!   `f128::to_string_fmt`, which needs `quadmath_snprintf`, is not available.
+//! - `native-f128` implements the arithmetic, comparisons, conversions and most `Float` methods on
+//!   top of the `f128` primitive. It requires a nightly compiler.
+//!
+//! The two features are mutually exclusive.
 #![allow(warnings)]
+#![cfg_attr(feature = "native-f128", feature(f128))]
 extern crate libc;
 extern crate num_traits;
 
 use std::f64;
+#[macro_use]
+mod literal;
+mod bignum;
+mod convert;
+mod decimal;
+mod decompose;
+mod f128_bits;
 mod f128_derive;
 mod f128_t;
 mod ffi;
+mod flags;
+mod hash;
+mod hex;
+mod minmax;
+#[cfg(feature = "native-f128")]
+mod native;
+mod parse;
+mod rounding;
+mod soft;
+mod total;
+mod ulp;
 
+#[cfg(all(feature = "soft-float", feature = "native-f128"))]
+compile_error!("the `soft-float` and `native-f128` features are mutually exclusive");
+
+pub use convert::{InexactError, IntoF128, TryFromF128Error};
+pub use f128_bits::F128Bits;
 pub use f128_derive::*;
 pub use f128_t::f128;
+pub use flags::{with_flags, FlagsGuard, FpFlags};
+pub use hash::BitHash;
+pub use parse::{ParseF128Error, ParseF128ErrorKind};
+pub use rounding::{with_rounding, RoundingGuard, RoundingMode};
+pub use total::TotalF128;
 
 #[cfg(test)]
 mod tests {
@@ -67,11 +107,15 @@ mod tests {
         let zero = f128::from_u8(0).unwrap();
         let other = -zero;
         let min = f128::MIN_POSITIVE;
+        let min_subnormal = f128::MIN_POSITIVE_SUBNORMAL;
 
         assert_eq!(half.classify(), FpCategory::Normal);
         assert_eq!(one.classify(), FpCategory::Normal);
         assert_eq!(pi.classify(), FpCategory::Normal);
-        assert_eq!(min.classify(), FpCategory::Subnormal);
+        assert_eq!(min.classify(), FpCategory::Normal);
+        assert_eq!((min / f128::TWO).classify(), FpCategory::Subnormal);
+        assert_eq!(min_subnormal.classify(), FpCategory::Subnormal);
+        assert_eq!(f128::MAX.classify(), FpCategory::Normal);
         assert_eq!(f128::INFINITY.classify(), FpCategory::Infinite);
         assert_eq!(f128::NEG_INFINITY.classify(), FpCategory::Infinite);
         assert_eq!(f128::NAN.classify(), FpCategory::Nan);
@@ -105,7 +149,7 @@ mod tests {
     fn test_to_string() {
         assert_eq!(f128::infinity().to_string().as_str(), "inf");
         assert_eq!(f128::neg_infinity().to_string().as_str(), "-inf");
-        assert_eq!(f128::nan().to_string().as_str(), "nan");
+        assert_eq!(f128::nan().to_string().as_str(), "NaN");
         assert_eq!(f128::neg_zero().to_string().as_str(), "-0");
         assert_eq!(f128::zero().to_string().as_str(), "0");
     }
@@ -151,6 +195,1365 @@ mod tests {
         assert_eq!(1u8, oneandhalf.to_u8().unwrap());
     }
 
+    #[test]
+    fn test_checked_int_conversions() {
+        use std::convert::TryFrom;
+        let f = |x: f64| f128::from_f64(x).unwrap();
+        let two_127 = f128::from_u128(1 << 127).unwrap();
+
+        for &x in &[f128::NAN, f128::INFINITY, f128::NEG_INFINITY] {
+            assert_eq!(x.to_i8(), None);
+            assert_eq!(x.to_u32(), None);
+            assert_eq!(x.to_i64(), None);
+            assert_eq!(x.to_usize(), None);
+            assert_eq!(x.to_i128(), None);
+            assert_eq!(x.to_u128(), None);
+        }
+        assert_eq!(f(127.9).to_i8(), Some(127));
+        assert_eq!(f(128.0).to_i8(), None);
+        assert_eq!(f(-128.9).to_i8(), Some(-128));
+        assert_eq!(f(-129.0).to_i8(), None);
+        assert_eq!(f(-0.9).to_u8(), Some(0));
+        assert_eq!(f(-1.0).to_u8(), None);
+        assert_eq!(f(255.5).to_u8(), Some(255));
+        assert_eq!(f(256.0).to_u8(), None);
+        assert_eq!(f(1e-300).to_u64(), Some(0));
+        assert_eq!(f(-65536.5).to_i32(), Some(-65536));
+        assert_eq!(f(4294967296.0).to_u32(), None);
+        assert_eq!((-two_127).to_i128(), Some(i128::MIN));
+        assert_eq!(two_127.to_i128(), None);
+        assert_eq!(two_127.to_u128(), Some(1 << 127));
+        assert_eq!((two_127 * f128::TWO).to_u128(), None);
+
+        assert_eq!(i32::try_from(f(3.0)), Ok(3));
+        assert_eq!(u32::try_from(-f128::ZERO), Ok(0));
+        assert_eq!(i64::try_from(f(-9007199254740992.0)), Ok(-1 << 53));
+        assert_eq!(i128::try_from(-two_127), Ok(i128::MIN));
+        assert_eq!(usize::try_from(f(7.0)), Ok(7));
+        assert_eq!(i32::try_from(f128::NAN), Err(TryFromF128Error::Nan));
+        assert_eq!(u8::try_from(f(256.0)), Err(TryFromF128Error::Overflow));
+        assert_eq!(
+            u64::try_from(f128::INFINITY),
+            Err(TryFromF128Error::Overflow)
+        );
+        assert_eq!(i128::try_from(two_127), Err(TryFromF128Error::Overflow));
+        assert_eq!(u32::try_from(f(-1.0)), Err(TryFromF128Error::Underflow));
+        assert_eq!(
+            isize::try_from(f128::NEG_INFINITY),
+            Err(TryFromF128Error::Underflow)
+        );
+        assert_eq!(i16::try_from(f(1.5)), Err(TryFromF128Error::Inexact));
+        assert_eq!(u32::try_from(f(-0.5)), Err(TryFromF128Error::Inexact));
+        assert_eq!(
+            u128::try_from(f128::from_bits(1)),
+            Err(TryFromF128Error::Inexact)
+        );
+        assert_eq!(
+            i8::try_from(f(-200.5)).unwrap_err().to_string(),
+            "f128 too small to fit in the integer type"
+        );
+    }
+
+    #[test]
+    fn test_saturating_casts() {
+        let two_127 = f128::from_u128(1 << 127).unwrap();
+        assert_eq!(f128::NAN.saturating_to::<i32>(), 0);
+        assert_eq!(f128::NAN.saturating_to::<u128>(), 0);
+        assert_eq!(f128::INFINITY.saturating_to::<i8>(), i8::MAX);
+        assert_eq!(f128::NEG_INFINITY.saturating_to::<i128>(), i128::MIN);
+        assert_eq!(f128::NEG_INFINITY.saturating_to::<usize>(), 0);
+        assert_eq!(two_127.saturating_to::<i128>(), i128::MAX);
+        assert_eq!((-two_127).saturating_to::<i128>(), i128::MIN);
+        assert_eq!((two_127 * f128::TWO).saturating_to::<u128>(), u128::MAX);
+        assert_eq!(two_127.saturating_to::<u128>(), 1 << 127);
+        let x: i16 = f128::from_f64(-2.75).unwrap().as_();
+        assert_eq!(x, -2);
+        assert_eq!(i64::from_f128(f128::NAN), 0);
+
+        // The same results as `as` on the same values in `f64`.
+        let mut state = 0x5851_f42d_4c95_7f2d_u64;
+        for i in 0..5000 {
+            let r = next_u64(&mut state);
+            let x = if i % 2 == 0 {
+                f64::from_bits(r)
+            } else {
+                (r as i64 >> (r % 64)) as f64 / (1 << (r % 8)) as f64
+            };
+            let y = f128::from_f64(x).unwrap();
+            assert_eq!(y.saturating_to::<i8>(), x as i8, "{}", x);
+            assert_eq!(y.saturating_to::<u8>(), x as u8, "{}", x);
+            assert_eq!(y.saturating_to::<i16>(), x as i16, "{}", x);
+            assert_eq!(y.saturating_to::<u16>(), x as u16, "{}", x);
+            assert_eq!(y.saturating_to::<i32>(), x as i32, "{}", x);
+            assert_eq!(y.saturating_to::<u32>(), x as u32, "{}", x);
+            assert_eq!(y.saturating_to::<i64>(), x as i64, "{}", x);
+            assert_eq!(y.saturating_to::<u64>(), x as u64, "{}", x);
+            assert_eq!(y.saturating_to::<i128>(), x as i128, "{}", x);
+            assert_eq!(y.saturating_to::<u128>(), x as u128, "{}", x);
+            assert_eq!(y.saturating_to::<isize>(), x as isize, "{}", x);
+            assert_eq!(y.saturating_to::<usize>(), x as usize, "{}", x);
+            assert_eq!(y.saturating_to::<f64>().to_bits(), x.to_bits(), "{}", x);
+        }
+    }
+
+    #[test]
+    fn test_exact_int_construction() {
+        use std::cmp::Ordering::*;
+        use RoundingMode::*;
+        let two_113 = 1u128 << 113;
+        let exact = |n: u128| f128::from_u128(n).unwrap();
+
+        assert_eq!(
+            bits(f128::try_from_exact(two_113).unwrap()),
+            bits(exact(two_113))
+        );
+        assert_eq!(
+            bits(f128::try_from_exact(i128::MIN).unwrap()),
+            bits(-exact(1 << 127))
+        );
+        assert_eq!(bits(f128::try_from_exact(-7i8).unwrap()), bits(-exact(7)));
+        assert_eq!(
+            bits(f128::try_from_exact(u64::MAX).unwrap()),
+            bits(exact(u64::MAX as u128))
+        );
+        let err = f128::try_from_exact(two_113 + 1).unwrap_err();
+        assert_eq!(bits(err.nearest()), bits(exact(two_113)));
+        assert_eq!(err.to_string(), "integer has no exact f128 representation");
+        assert_eq!(
+            bits(f128::try_from_exact(u128::MAX).unwrap_err().nearest()),
+            bits(exact(1 << 127) * f128::TWO)
+        );
+        assert!(f128::try_from_exact(i128::MAX).is_err());
+        assert!(f128::try_from_exact(-(two_113 as i128) - 3).is_err());
+
+        let with = f128::from_u128_with_rounding;
+        let check = |(x, o): (f128, std::cmp::Ordering), n: u128, order| {
+            assert_eq!((bits(x), o), (bits(exact(n)), order));
+        };
+        check(with(two_113 + 1, NearestEven), two_113, Less);
+        check(with(two_113 + 3, NearestEven), two_113 + 4, Greater);
+        check(with(two_113 + 1, Up), two_113 + 2, Greater);
+        check(with(two_113 + 1, Down), two_113, Less);
+        check(with(two_113 + 1, TowardZero), two_113, Less);
+        check(with(two_113 + 2, Up), two_113 + 2, Equal);
+        check(with(12345, Down), 12345, Equal);
+        check(with(u128::MAX, Down), u128::MAX - (1 << 15) + 1, Less);
+        let (up, order) = with(u128::MAX, Up);
+        assert_eq!(
+            (bits(up), order),
+            (bits(exact(1 << 127) * f128::TWO), Greater)
+        );
+
+        let n = -(two_113 as i128) - 1;
+        let (x, order) = f128::from_i128_with_rounding(n, Up);
+        assert_eq!((bits(x), order), (bits(-exact(two_113)), Greater));
+        let (x, order) = f128::from_i128_with_rounding(n, Down);
+        assert_eq!((bits(x), order), (bits(-exact(two_113 + 2)), Less));
+        let (x, order) = f128::from_i128_with_rounding(i128::MAX, NearestEven);
+        assert_eq!((bits(x), order), (bits(exact(1 << 127)), Greater));
+        assert_eq!((-5i16).into_f128_with_rounding(Up).1, Equal);
+
+        // The rounding mode is the one passed in, not the current one.
+        let (x, _) = with_rounding(Down, || with(two_113 + 1, Up));
+        assert_eq!(bits(x), bits(exact(two_113 + 2)));
+    }
+
+    #[test]
+    fn test_num_cast() {
+        fn cast<T: ToPrimitive>(n: T) -> Option<f128> {
+            <f128 as NumCast>::from(n)
+        }
+        let exact = |n: u128| f128::from_u128(n).unwrap();
+
+        assert_eq!(bits(cast(u64::MAX).unwrap()), bits(exact(u64::MAX as u128)));
+        assert_eq!(
+            bits(cast(i64::MIN + 1).unwrap()),
+            bits(-exact((1 << 63) - 1))
+        );
+        let wide = (1u128 << 112) + 12345;
+        assert_eq!(bits(cast(wide).unwrap()), bits(exact(wide)));
+        assert_eq!(bits(cast(-(wide as i128)).unwrap()), bits(-exact(wide)));
+        assert_eq!(bits(cast(u128::MAX).unwrap()), bits(exact(u128::MAX)));
+        assert_eq!(
+            bits(cast(0.1f64).unwrap()),
+            bits(f128::from_f64(0.1).unwrap())
+        );
+        assert_eq!(
+            bits(cast(-2.5f32).unwrap()),
+            bits(f128::from_f32(-2.5).unwrap())
+        );
+        assert_eq!(bits(cast(-0.0f64).unwrap()), bits(-f128::ZERO));
+        assert_eq!(
+            bits(cast(1e300f64).unwrap()),
+            bits(f128::from_f64(1e300).unwrap())
+        );
+        assert!(cast(f64::NAN).unwrap().is_nan());
+        assert!(cast(f32::NEG_INFINITY).unwrap() == f128::NEG_INFINITY);
+        assert_eq!(bits(cast(exact(wide)).unwrap()), bits(exact(wide)));
+
+        // An `f128` comes through unchanged.
+        assert_eq!(bits(cast(f128::PI).unwrap()), bits(f128::PI));
+        assert_eq!(bits(f128::PI.cast::<f128>().unwrap()), bits(f128::PI));
+        assert!(cast(f128::NAN).unwrap().is_nan());
+
+        // Any other type that only converts to the primitives: non-integers below 2^53 go
+        // through `f64` rather than being truncated, and wide integers are exact whichever way
+        // `f64` rounds them.
+        struct Opaque(f128);
+        impl ToPrimitive for Opaque {
+            fn to_i64(&self) -> Option<i64> {
+                self.0.to_i64()
+            }
+            fn to_u64(&self) -> Option<u64> {
+                self.0.to_u64()
+            }
+            fn to_i128(&self) -> Option<i128> {
+                self.0.to_i128()
+            }
+            fn to_u128(&self) -> Option<u128> {
+                self.0.to_u128()
+            }
+            fn to_f64(&self) -> Option<f64> {
+                self.0.to_f64()
+            }
+        }
+        let below_four = f128::from_u8(4).unwrap() - f128::from_bits(0x3f9b << 112);
+        assert_eq!(bits(cast(Opaque(below_four)).unwrap()), bits(exact(4)));
+        let half_past = exact(1 << 60) + f128::from_f64(0.5).unwrap();
+        assert_eq!(bits(cast(Opaque(half_past)).unwrap()), bits(exact(1 << 60)));
+        let negative = -f128::from_f64(2.75).unwrap();
+        assert_eq!(bits(cast(Opaque(negative)).unwrap()), bits(negative));
+        let odd = exact((1 << 100) + 1);
+        let up = with_rounding(RoundingMode::Up, || cast(Opaque(odd)).unwrap());
+        assert_eq!(bits(up), bits(odd));
+
+        // And back, to whatever type is asked for.
+        let big = exact(wide) * f128::from_u8(4).unwrap();
+        assert_eq!(big.cast::<u128>(), Some(4 * wide));
+        assert_eq!((-big).cast::<i128>(), Some(-4 * wide as i128));
+        assert_eq!(big.cast::<u64>(), None);
+        assert_eq!(f128::from_f64(-7.9).unwrap().cast::<i8>(), Some(-7));
+        assert_eq!(f128::NAN.cast::<u32>(), None);
+        assert_eq!(f128::ONE.cast::<f64>(), Some(1.0));
+        assert_eq!(<u128 as NumCast>::from(big), Some(4 * wide));
+    }
+
+    #[test]
+    fn test_constant_fields() {
+        // Reads the fields from the bytes in memory, whose order depends on the target.
+        fn fields(x: f128) -> (bool, u32, u128) {
+            let bits = if cfg!(target_endian = "little") {
+                u128::from_le_bytes(x.0)
+            } else {
+                u128::from_be_bytes(x.0)
+            };
+            (
+                bits >> 127 == 1,
+                (bits >> 112) as u32 & 0x7fff,
+                bits & (1 << 112) - 1,
+            )
+        }
+
+        let all_ones = (1 << 112) - 1;
+        let table = [
+            (f128::ZERO, false, 0, 0),
+            (f128::NEG_ZERO, true, 0, 0),
+            (f128::ONE, false, 0x3fff, 0),
+            (f128::TWO, false, 0x4000, 0),
+            (f128::MAX, false, 0x7ffe, all_ones),
+            (f128::MIN, true, 0x7ffe, all_ones),
+            (f128::MIN_POSITIVE, false, 1, 0),
+            (f128::MIN_POSITIVE_SUBNORMAL, false, 0, 1),
+            (f128::EPSILON, false, 0x3fff - 112, 0),
+            (f128::INFINITY, false, 0x7fff, 0),
+            (f128::NEG_INFINITY, true, 0x7fff, 0),
+            (f128::NAN, false, 0x7fff, all_ones),
+            (f128::SIGN_BIT, true, 0, 0),
+            (f128::EXPONENT_BITS, false, 0x7fff, 0),
+            (f128::FRACTION_BITS, false, 0, all_ones),
+            (
+                f128::PI,
+                false,
+                0x4000,
+                0x921f_b544_42d1_8469_898c_c517_01b8,
+            ),
+            (f128::E, false, 0x4000, 0x5bf0_a8b1_4576_9535_5fb8_ac40_4e7a),
+            (
+                f128::LN_2,
+                false,
+                0x3ffe,
+                0x62e4_2fef_a39e_f357_93c7_6730_07e6,
+            ),
+        ];
+        for &(x, sign, exp, fraction) in &table {
+            assert_eq!(fields(x), (sign, exp, fraction), "{:?}", x);
+            assert_eq!(x.0, bits(x).to_ne_bytes());
+        }
+
+        assert_eq!(f128::RADIX, 2);
+        assert_eq!(f128::MANTISSA_DIGITS, 113);
+        assert_eq!(f128::DIGITS, 33);
+        let two = f128::TWO;
+        assert_eq!(
+            bits(f128::EPSILON),
+            bits(two.powi(1 - f128::MANTISSA_DIGITS as i32))
+        );
+        assert_eq!(bits(f128::MIN_POSITIVE), bits(two.powi(f128::MIN_EXP)));
+        assert_eq!(
+            bits(f128::MIN_POSITIVE_SUBNORMAL),
+            bits(two.powi(f128::MIN_EXP + 1 - f128::MANTISSA_DIGITS as i32))
+        );
+        let max = (two - f128::EPSILON) * two.powi(f128::MAX_EXP as i32);
+        assert_eq!(bits(f128::MAX), bits(max));
+        assert_eq!(bits(f128::MIN), bits(-max));
+        assert!(f128::ONE + f128::EPSILON > f128::ONE);
+        assert!(f128::ONE + f128::EPSILON / two == f128::ONE);
+        assert!((f128::MAX * two).is_infinite());
+        assert!((f128::MIN_POSITIVE_SUBNORMAL / two).is_zero());
+        assert!(f128::from_u32(10)
+            .unwrap()
+            .powi(f128::MAX_10_EXP as i32)
+            .is_finite());
+        assert!(f128::from_u32(10)
+            .unwrap()
+            .powi(f128::MAX_10_EXP as i32 + 1)
+            .is_infinite());
+        assert!(f128::from_u32(10)
+            .unwrap()
+            .powi(f128::MIN_10_EXP)
+            .is_normal());
+        assert!(!f128::from_u32(10)
+            .unwrap()
+            .powi(f128::MIN_10_EXP - 1)
+            .is_normal());
+    }
+
+    #[test]
+    fn test_bit_fields() {
+        const ONE_BITS: u128 = f128::ONE.to_bits();
+        const ONE_AND_HALF: f128 = f128::ONE.with_fract_bits(1 << 111);
+        assert_eq!(ONE_BITS, 0x3fff_0000_0000_0000_0000_0000_0000_0000);
+        assert_eq!(ONE_AND_HALF.to_bits(), f128!(1.5).to_bits());
+
+        let mut state = 0x853c_49e6_748f_ea9b;
+        for _ in 0..1_000 {
+            let x = random_operand(&mut state);
+            let b = x.to_bits();
+            assert_eq!(f128::from_bits(b).to_bits(), b);
+            assert_eq!(b, bits(x));
+            let rebuilt = f128::ZERO
+                .with_sign_bit(x.is_sign_bit_set())
+                .with_exp_bits(x.exp_bits())
+                .with_fract_bits(x.fract_bits());
+            assert_eq!(rebuilt.to_bits(), b);
+            assert_eq!(x.is_sign_bit_set(), x.is_sign_negative());
+            assert_eq!(x.with_sign_bit(!x.is_sign_bit_set()).to_bits(), bits(-x));
+        }
+
+        let x = f128!(-6);
+        assert_eq!(
+            (x.is_sign_bit_set(), x.exp_bits(), x.fract_bits()),
+            (true, 0x4001, 1 << 111)
+        );
+        assert_eq!(bits(x.with_exp_bits(0x3fff)), bits(f128!(-1.5)));
+        assert_eq!(bits(x.with_sign_bit(false)), bits(f128!(6)));
+        assert_eq!(
+            bits(f128::ZERO.with_fract_bits(1)),
+            bits(f128::MIN_POSITIVE_SUBNORMAL)
+        );
+        assert!(f128::INFINITY.with_fract_bits(1).is_nan());
+        assert!(std::panic::catch_unwind(|| f128::ONE.with_exp_bits(0x8000)).is_err());
+        assert!(std::panic::catch_unwind(|| f128::ONE.with_fract_bits(1 << 112)).is_err());
+    }
+
+    #[test]
+    fn test_bits_view() {
+        let x = F128Bits::from(f128!(-1.5));
+        assert_eq!(x.0, 0xbfff_8000_0000_0000_0000_0000_0000_0000);
+        assert_eq!(x & F128Bits::SIGN_MASK, F128Bits::SIGN_MASK);
+        assert_eq!((x & F128Bits::EXPONENT_MASK) >> 112, F128Bits(0x3fff));
+        assert_eq!(x & F128Bits::FRACTION_MASK, F128Bits(1 << 111));
+        assert_eq!(
+            F128Bits::SIGN_MASK | F128Bits::EXPONENT_MASK | F128Bits::FRACTION_MASK,
+            !F128Bits(0)
+        );
+        assert_eq!(bits((x ^ F128Bits::SIGN_MASK).into()), bits(f128!(1.5)));
+        assert_eq!(bits((x & !F128Bits::SIGN_MASK).to_f128()), bits(f128!(1.5)));
+        assert_eq!(x << 1 >> 1, x & !F128Bits::SIGN_MASK);
+
+        let mut y = x;
+        y |= F128Bits(1);
+        y &= !F128Bits::SIGN_MASK;
+        y ^= F128Bits(1 << 112);
+        y <<= 4;
+        y >>= 4;
+        assert_eq!(y, F128Bits(0x0ffe_8000_0000_0000_0000_0000_0000_0001));
+        assert_eq!(
+            format!("{:?}", F128Bits(1)),
+            "F128Bits(0x00000000000000000000000000000001)"
+        );
+        assert_eq!(
+            format!("{:x}", F128Bits::EXPONENT_MASK),
+            "7fff0000000000000000000000000000"
+        );
+
+        // Conversions keep every bit, NaN payloads included.
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..1_000 {
+            let x = random_operand(&mut state);
+            assert_eq!(bits(F128Bits::from(x).into()), bits(x));
+            assert_eq!(F128Bits::from(x).0, bits(x));
+        }
+    }
+
+    #[test]
+    fn test_scale_by_pow2() {
+        assert_eq!(bits(f128!(1.5).scale_by_pow2(3)), bits(f128!(12)));
+        assert_eq!(bits(f128!(-12).scale_by_pow2(-3)), bits(f128!(-1.5)));
+        assert_eq!(
+            bits(f128::ONE.scale_by_pow2(16383)),
+            bits(f128!("0x1p16383"))
+        );
+        assert_eq!(
+            bits(f128::MAX.scale_by_pow2(-32765)),
+            bits(f128!("0x1.ffffffffffffffffffffffffffffp-16382"))
+        );
+        assert_eq!(
+            bits(f128::ONE.scale_by_pow2(-16494)),
+            bits(f128::MIN_POSITIVE_SUBNORMAL)
+        );
+        assert_eq!(
+            bits(f128::MIN_POSITIVE_SUBNORMAL.scale_by_pow2(16494)),
+            bits(f128::ONE)
+        );
+        // Halfway between zero and the smallest subnormal rounds to even, that is to zero.
+        assert_eq!(bits(f128::ONE.scale_by_pow2(-16495)), bits(f128::ZERO));
+        assert_eq!(
+            bits(f128!(-3).scale_by_pow2(-16495)),
+            bits(-f128::MIN_POSITIVE_SUBNORMAL.scale_by_pow2(1))
+        );
+        assert!(f128::MAX.scale_by_pow2(1).is_infinite());
+        assert!(f128::ONE.scale_by_pow2(i32::max_value()).is_infinite());
+        assert_eq!(
+            bits(f128::MAX.scale_by_pow2(i32::min_value())),
+            bits(f128::ZERO)
+        );
+        assert_eq!(bits(f128!(-0.0).scale_by_pow2(5)), bits(f128!(-0.0)));
+        assert_eq!(
+            bits(f128::NEG_INFINITY.scale_by_pow2(-5)),
+            bits(f128::NEG_INFINITY)
+        );
+        assert!(f128::NAN.scale_by_pow2(1).is_nan());
+
+        let (x, raised) = with_flags(|| f128::MAX.scale_by_pow2(1));
+        assert!(x.is_infinite());
+        assert!(raised.contains(FpFlags::OVERFLOW));
+        let (_, raised) = with_flags(|| f128!(3).scale_by_pow2(-16495));
+        assert!(raised.contains(FpFlags::UNDERFLOW | FpFlags::INEXACT));
+        let (_, raised) = with_flags(|| f128!(3).scale_by_pow2(100));
+        assert_eq!(raised, FpFlags::empty());
+        let x = with_rounding(RoundingMode::Up, || f128::ONE.scale_by_pow2(-16495));
+        assert_eq!(bits(x), bits(f128::MIN_POSITIVE_SUBNORMAL));
+
+        // Multiplying by an exact power of two rounds the same way.
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+        for i in 0..2_000 {
+            let x = random_operand(&mut state);
+            let n = (i % 401) - 200;
+            let p = f128::ONE.with_exp_bits((0x3fff + n) as u32);
+            assert_eq!(bits(x.scale_by_pow2(n)), bits(x * p), "{:?} * 2^{}", x, n);
+        }
+    }
+
+    #[test]
+    fn test_next_and_ulp() {
+        let tiny = f128::MIN_POSITIVE_SUBNORMAL;
+        assert_eq!(bits(f128::ONE.next_up()), bits(f128::ONE + f128::EPSILON));
+        assert_eq!(bits(f128::ZERO.next_up()), bits(tiny));
+        assert_eq!(bits(f128!(-0.0).next_up()), bits(tiny));
+        assert_eq!(bits((-tiny).next_up()), bits(f128!(-0.0)));
+        assert_eq!(bits(f128::ZERO.next_down()), bits(-tiny));
+        assert_eq!(bits(tiny.next_down()), bits(f128::ZERO));
+        assert_eq!(bits(f128::MAX.next_up()), bits(f128::INFINITY));
+        assert_eq!(bits(f128::INFINITY.next_up()), bits(f128::INFINITY));
+        assert_eq!(bits(f128::NEG_INFINITY.next_up()), bits(f128::MIN));
+        assert_eq!(
+            bits(f128::NEG_INFINITY.next_down()),
+            bits(f128::NEG_INFINITY)
+        );
+        assert_eq!(
+            bits((f128::MIN_POSITIVE - tiny).next_up()),
+            bits(f128::MIN_POSITIVE)
+        );
+        assert!(f128::NAN.next_up().is_nan());
+        assert!(f128::NAN.next_down().is_nan());
+
+        assert_eq!(bits(f128::ZERO.next_after(f128!(-0.0))), bits(f128!(-0.0)));
+        assert_eq!(bits(f128!(-0.0).next_after(f128::ZERO)), bits(f128::ZERO));
+        assert_eq!(bits(f128::ONE.next_after(f128::ONE)), bits(f128::ONE));
+        assert_eq!(bits(f128::ZERO.next_after(f128::ONE)), bits(tiny));
+        assert_eq!(bits(f128::ZERO.next_after(-f128::ONE)), bits(-tiny));
+        assert_eq!(bits(f128::INFINITY.next_after(f128::ZERO)), bits(f128::MAX));
+        assert!(f128::ONE.next_after(f128::NAN).is_nan());
+        assert!(f128::NAN.next_after(f128::ONE).is_nan());
+
+        assert_eq!(bits(f128::ONE.ulp()), bits(f128::EPSILON));
+        assert_eq!(bits(f128!(-1.5).ulp()), bits(f128::EPSILON));
+        assert_eq!(bits(f128!(2).ulp()), bits(f128::EPSILON * f128!(2)));
+        assert_eq!(bits(f128::ZERO.ulp()), bits(tiny));
+        assert_eq!(bits(f128!(-0.0).ulp()), bits(tiny));
+        assert_eq!(bits(f128::MIN_POSITIVE.ulp()), bits(tiny));
+        assert_eq!(
+            bits(f128::MIN_POSITIVE.scale_by_pow2(1).ulp()),
+            bits(tiny.scale_by_pow2(1))
+        );
+        assert_eq!(bits(f128::MAX.ulp()), bits(f128!("0x1p16271")));
+        assert!(f128::INFINITY.ulp().is_nan());
+        assert!(f128::NAN.ulp().is_nan());
+
+        assert_eq!(f128::ONE.ulps_between(f128::ONE), 0);
+        assert_eq!(f128::ZERO.ulps_between(f128!(-0.0)), 0);
+        assert_eq!(tiny.ulps_between(-tiny), 2);
+        assert_eq!(f128::ONE.ulps_between(f128::ONE.next_up()), 1);
+        assert_eq!(f128::ONE.ulps_between(f128!(2)), 1 << 112);
+        assert_eq!(f128!(2).ulps_between(f128::ONE), 1 << 112);
+        assert_eq!(f128::MAX.ulps_between(f128::INFINITY), 1);
+        assert_eq!(
+            f128::NEG_INFINITY.ulps_between(f128::INFINITY),
+            2 * f128::ZERO.ulps_between(f128::INFINITY)
+        );
+
+        // Away from zero, the steps agree with C's nextafterq and with the ulp.
+        let mut state = 0x6a09_e667_f3bc_c909;
+        for _ in 0..10_000 {
+            let x = random_operand(&mut state);
+            let up = unsafe { nextafterq_f(x, f128::INFINITY) };
+            let down = unsafe { nextafterq_f(x, f128::NEG_INFINITY) };
+            if x.is_nan() {
+                assert!(x.next_up().is_nan() && x.next_down().is_nan());
+                continue;
+            }
+            if x != f128::ZERO {
+                assert_eq!(bits(x.next_up()), bits(up), "{:?}", x);
+                assert_eq!(bits(x.next_down()), bits(down), "{:?}", x);
+            }
+            if x.is_finite() && x.abs() < f128::MAX {
+                let away = if x.is_sign_negative() {
+                    x.next_down()
+                } else {
+                    x.next_up()
+                };
+                assert_eq!(bits((away - x).abs()), bits(x.ulp()), "{:?}", x);
+                assert_eq!(x.ulps_between(away), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_frexp_and_scaling() {
+        let tiny = f128::MIN_POSITIVE_SUBNORMAL;
+        assert_eq!(f128!(12).frexp(), (f128!(0.75), 4));
+        assert_eq!(f128!(-1).frexp(), (f128!(-0.5), 1));
+        assert_eq!(
+            f128::MAX.frexp(),
+            (f128::ONE - f128::EPSILON / f128!(2), 16384)
+        );
+        assert_eq!(f128::MIN_POSITIVE.frexp(), (f128!(0.5), -16381));
+        assert_eq!(tiny.frexp(), (f128!(0.5), -16493));
+        let (fraction, exponent) = f128!(-0.0).frexp();
+        assert_eq!((bits(fraction), exponent), (bits(f128!(-0.0)), 0));
+        let (fraction, exponent) = f128::NEG_INFINITY.frexp();
+        assert_eq!((bits(fraction), exponent), (bits(f128::NEG_INFINITY), 0));
+        let (fraction, exponent) = f128::NAN.frexp();
+        assert!(fraction.is_nan());
+        assert_eq!(exponent, 0);
+
+        assert_eq!(bits(f128!(0.75).ldexp(4)), bits(f128!(12)));
+        assert_eq!(bits(f128!(0.5).ldexp(-16493)), bits(tiny));
+        assert_eq!(bits(f128::ONE.ldexp(-16495)), bits(f128::ZERO));
+        assert_eq!(bits(f128!(-0.0).ldexp(100)), bits(f128!(-0.0)));
+        assert!(f128::MAX.ldexp(1).is_infinite());
+        assert!(f128::NAN.ldexp(1).is_nan());
+        assert_eq!(bits(f128!(3).scalbn(-2)), bits(f128!(0.75)));
+        assert_eq!(bits(tiny.scalbn(16494)), bits(f128::ONE));
+        assert_eq!(
+            bits(f128::NEG_INFINITY.scalbn(-5)),
+            bits(f128::NEG_INFINITY)
+        );
+        assert_eq!(bits(f128!(3).scalbln(-2)), bits(f128!(0.75)));
+        assert_eq!(bits(tiny.scalbln(i64::max_value())), bits(f128::INFINITY));
+        assert_eq!(bits(f128::MAX.scalbln(i64::min_value())), bits(f128::ZERO));
+        assert_eq!(bits(f128!(-1).scalbln(1 << 40)), bits(f128::NEG_INFINITY));
+        let x = with_rounding(RoundingMode::Up, || f128::ONE.scalbn(-16495));
+        assert_eq!(bits(x), bits(tiny));
+        let x = with_rounding(RoundingMode::Up, || f128::ONE.scalbln(-16495));
+        assert_eq!(bits(x), bits(tiny));
+
+        let mut state = 0xbb67_ae85_84ca_a73b;
+        for _ in 0..2_000 {
+            let x = random_operand(&mut state);
+            let (fraction, exponent) = x.frexp();
+            if x.is_finite() && x != f128::ZERO {
+                assert!(fraction.abs() >= f128!(0.5) && fraction.abs() < f128::ONE);
+                assert_eq!(bits(fraction.ldexp(exponent)), bits(x), "{:?}", x);
+                assert_eq!(x.ilogb(), Some(exponent - 1), "{:?}", x);
+                assert_eq!(bits(x.logb()), bits((exponent - 1).into()), "{:?}", x);
+            }
+            if !x.is_nan() {
+                assert_eq!(bits(x.scalbn(7)), bits(x.scale_by_pow2(7)), "{:?}", x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_modf_and_logb() {
+        assert_eq!(f128!(2.75).modf(), (f128!(0.75), f128!(2)));
+        assert_eq!(f128!(-2.75).modf(), (f128!(-0.75), f128!(-2)));
+        let (fractional, integral) = f128!(-3).modf();
+        assert_eq!(
+            (bits(fractional), bits(integral)),
+            (bits(f128!(-0.0)), bits(f128!(-3)))
+        );
+        let (fractional, integral) = f128!(-0.25).modf();
+        assert_eq!(
+            (bits(fractional), bits(integral)),
+            (bits(f128!(-0.25)), bits(f128!(-0.0)))
+        );
+        let (fractional, integral) = f128::NEG_INFINITY.modf();
+        assert_eq!(
+            (bits(fractional), bits(integral)),
+            (bits(f128!(-0.0)), bits(f128::NEG_INFINITY))
+        );
+        let (fractional, integral) = f128::NAN.modf();
+        assert!(fractional.is_nan() && integral.is_nan());
+        assert_eq!(f128::MAX.modf(), (f128::ZERO, f128::MAX));
+        assert_eq!(
+            f128::MIN_POSITIVE_SUBNORMAL.modf(),
+            (f128::MIN_POSITIVE_SUBNORMAL, f128::ZERO)
+        );
+
+        assert_eq!(f128::ONE.ilogb(), Some(0));
+        assert_eq!(f128!(-0.75).ilogb(), Some(-1));
+        assert_eq!(f128::MAX.ilogb(), Some(16383));
+        assert_eq!(f128::MIN_POSITIVE.ilogb(), Some(-16382));
+        assert_eq!(f128::MIN_POSITIVE_SUBNORMAL.ilogb(), Some(-16494));
+        assert_eq!(f128::ZERO.ilogb(), None);
+        assert_eq!(f128::NEG_INFINITY.ilogb(), None);
+        assert_eq!(f128::NAN.ilogb(), None);
+        let (_, raised) = with_flags(|| f128::ZERO.ilogb());
+        assert_eq!(raised, FpFlags::INVALID);
+        let (_, raised) = with_flags(|| f128::ONE.ilogb());
+        assert_eq!(raised, FpFlags::empty());
+
+        assert_eq!(bits(f128!(-1000).logb()), bits(f128!(9)));
+        assert_eq!(
+            bits(f128::MIN_POSITIVE_SUBNORMAL.logb()),
+            bits(f128!(-16494))
+        );
+        assert_eq!(bits(f128!(-0.0).logb()), bits(f128::NEG_INFINITY));
+        assert_eq!(bits(f128::NEG_INFINITY.logb()), bits(f128::INFINITY));
+        assert!(f128::NAN.logb().is_nan());
+    }
+
+    #[test]
+    fn test_remainder_and_sign() {
+        assert_eq!(bits(f128!(7).remainder(f128!(2))), bits(f128!(-1)));
+        assert_eq!(bits(f128!(5).remainder(f128!(2))), bits(f128::ONE));
+        assert_eq!(bits(f128!(-4).remainder(f128!(2))), bits(f128!(-0.0)));
+        assert_eq!(bits(f128!(3).remainder(f128::INFINITY)), bits(f128!(3)));
+        assert_eq!(
+            bits(f128::MAX.remainder(f128::MIN_POSITIVE_SUBNORMAL)),
+            bits(f128::ZERO)
+        );
+        assert!(f128::INFINITY.remainder(f128!(2)).is_nan());
+        assert!(f128!(3).remainder(f128::ZERO).is_nan());
+        assert!(f128!(3).remainder(f128::NAN).is_nan());
+
+        let (remainder, quotient) = f128!(7).remquo(f128!(2));
+        assert_eq!(bits(remainder), bits(f128!(-1)));
+        assert_eq!(quotient & 7, 4);
+        let (remainder, quotient) = f128!(-7).remquo(f128!(2));
+        assert_eq!(bits(remainder), bits(f128::ONE));
+        assert_eq!(-quotient & 7, 4);
+        let (remainder, quotient) = f128!(29).remquo(f128!(-3));
+        assert_eq!(bits(remainder), bits(f128!(-1)));
+        assert_eq!(-quotient & 7, 10 & 7);
+        let (remainder, quotient) = f128::ZERO.remquo(f128!(3));
+        assert_eq!((bits(remainder), quotient), (bits(f128::ZERO), 0));
+        assert!(f128::NAN.remquo(f128::ONE).0.is_nan());
+        assert!(f128::ONE.remquo(f128::ZERO).0.is_nan());
+
+        assert_eq!(bits(f128!(5).fdim(f128!(3))), bits(f128!(2)));
+        assert_eq!(bits(f128!(3).fdim(f128!(5))), bits(f128::ZERO));
+        assert_eq!(bits(f128!(-0.0).fdim(f128::ZERO)), bits(f128::ZERO));
+        assert_eq!(bits(f128::INFINITY.fdim(f128::MAX)), bits(f128::INFINITY));
+        assert_eq!(bits(f128::MAX.fdim(-f128::MAX)), bits(f128::INFINITY));
+        assert!(f128::NAN.fdim(f128::ONE).is_nan());
+        assert!(f128::ONE.fdim(f128::NAN).is_nan());
+        let x = with_rounding(RoundingMode::TowardZero, || f128::MAX.fdim(-f128::MAX));
+        assert_eq!(bits(x), bits(f128::MAX));
+        let x = with_rounding(RoundingMode::Down, || f128!(3).fdim(f128!(5)));
+        assert_eq!(bits(x), bits(f128::ZERO));
+        assert_eq!(bits(f128!(5).abs_sub(f128!(3))), bits(f128!(2)));
+
+        assert_eq!(bits(f128!(3).copysign(f128!(-0.0))), bits(f128!(-3)));
+        assert_eq!(bits(f128!(-3).copysign(f128::ONE)), bits(f128!(3)));
+        assert_eq!(
+            bits(f128::ZERO.copysign(f128::NEG_INFINITY)),
+            bits(f128!(-0.0))
+        );
+        assert_eq!(bits(f128::ONE.copysign(-f128::NAN)), bits(-f128::ONE));
+        assert_eq!(bits(f128::NAN.copysign(-f128::ONE)), bits(-f128::NAN));
+    }
+
+    #[test]
+    fn test_round_to_integer() {
+        let cases = [
+            // x, nearest even, toward zero, up, down
+            ("2.5", "2", "2", "3", "2"),
+            ("3.5", "4", "3", "4", "3"),
+            ("-2.5", "-2", "-2", "-2", "-3"),
+            ("0.25", "0", "0", "1", "0"),
+            ("-0.25", "-0", "-0", "-0", "-1"),
+            ("7", "7", "7", "7", "7"),
+            ("-0", "-0", "-0", "-0", "-0"),
+            (
+                "0x1.0000000000000000000000000001p111",
+                "0x1p111",
+                "0x1p111",
+                "0x1.0000000000000000000000000002p111",
+                "0x1p111",
+            ),
+            (
+                "0x1.0000000000000000000000000001p112",
+                "0x1.0000000000000000000000000001p112",
+                "0x1.0000000000000000000000000001p112",
+                "0x1.0000000000000000000000000001p112",
+                "0x1.0000000000000000000000000001p112",
+            ),
+        ];
+        let modes = [
+            RoundingMode::NearestEven,
+            RoundingMode::TowardZero,
+            RoundingMode::Up,
+            RoundingMode::Down,
+        ];
+        for &(x, nearest, toward_zero, up, down) in cases.iter() {
+            let x = f128::parse(x).unwrap();
+            for (&mode, expected) in modes.iter().zip([nearest, toward_zero, up, down].iter()) {
+                let expected = f128::parse(expected).unwrap();
+                let (rounded, raised) = with_rounding(mode, || with_flags(|| x.rint()));
+                assert_eq!(bits(rounded), bits(expected), "rint({:?}) in {:?}", x, mode);
+                assert_eq!(raised.contains(FpFlags::INEXACT), expected != x);
+                let (rounded, raised) = with_rounding(mode, || with_flags(|| x.nearbyint()));
+                assert_eq!(
+                    bits(rounded),
+                    bits(expected),
+                    "nearbyint({:?}) in {:?}",
+                    x,
+                    mode
+                );
+                assert_eq!(raised, FpFlags::empty());
+                let n = with_rounding(mode, || x.lrint());
+                assert_eq!(n, expected.to_i64(), "lrint({:?}) in {:?}", x, mode);
+            }
+        }
+        assert_eq!(bits(f128::MAX.rint()), bits(f128::MAX));
+        assert_eq!(
+            bits(f128::NEG_INFINITY.nearbyint()),
+            bits(f128::NEG_INFINITY)
+        );
+        assert!(f128::NAN.rint().is_nan());
+        assert!(with_rounding(RoundingMode::Up, || f128::NAN.nearbyint()).is_nan());
+
+        assert_eq!(f128!(-9223372036854775808).lrint(), Some(i64::min_value()));
+        assert_eq!(f128!(9223372036854775807.4).lrint(), Some(i64::max_value()));
+        assert_eq!(f128!(9223372036854775807.5).lrint(), None);
+        assert_eq!(
+            f128!(-9223372036854775808.5).lrint(),
+            Some(i64::min_value())
+        );
+        let n = with_rounding(RoundingMode::Down, || f128!(-9223372036854775808.5).lrint());
+        assert_eq!(n, None);
+        assert_eq!(f128::INFINITY.lrint(), None);
+        assert_eq!(f128::NAN.lrint(), None);
+        let (_, raised) = with_flags(|| f128::NAN.lrint());
+        assert!(raised.contains(FpFlags::INVALID));
+
+        assert_eq!(f128!(2.5).llround(), Some(3));
+        assert_eq!(f128!(-2.5).llround(), Some(-3));
+        assert_eq!(f128!(2.49).llround(), Some(2));
+        assert_eq!(f128!(-0.0).llround(), Some(0));
+        assert_eq!(
+            f128!(-9223372036854775808.4).llround(),
+            Some(i64::min_value())
+        );
+        assert_eq!(f128!(-9223372036854775808.5).llround(), None);
+        assert_eq!(
+            f128!(9223372036854775807.49).llround(),
+            Some(i64::max_value())
+        );
+        assert_eq!(f128!(9223372036854775807.5).llround(), None);
+        assert_eq!(f128::NEG_INFINITY.llround(), None);
+        assert_eq!(f128::NAN.llround(), None);
+        // The current rounding mode doesn't matter.
+        assert_eq!(
+            with_rounding(RoundingMode::Down, || f128!(2.5).llround()),
+            Some(3)
+        );
+    }
+
+    #[test]
+    fn test_float_constants() {
+        // Each constant is the nearest f128 to a 50-digit expansion, none of which is close enough
+        // to halfway between two f128s for the digits beyond to matter.
+        let table = [
+            (
+                f128::E,
+                "2.7182818284590452353602874713526624977572470937000",
+            ),
+            (
+                f128::PI,
+                "3.1415926535897932384626433832795028841971693993751",
+            ),
+            (
+                f128::TAU,
+                "6.2831853071795864769252867665590057683943387987502",
+            ),
+            (
+                f128::FRAC_PI_2,
+                "1.5707963267948966192313216916397514420985846996876",
+            ),
+            (
+                f128::FRAC_PI_3,
+                "1.0471975511965977461542144610931676280657231331250",
+            ),
+            (
+                f128::FRAC_PI_4,
+                "0.78539816339744830961566084581987572104929234984378",
+            ),
+            (
+                f128::FRAC_PI_6,
+                "0.52359877559829887307710723054658381403286156656252",
+            ),
+            (
+                f128::FRAC_PI_8,
+                "0.39269908169872415480783042290993786052464617492189",
+            ),
+            (
+                f128::FRAC_1_PI,
+                "0.31830988618379067153776752674502872406891929148091",
+            ),
+            (
+                f128::FRAC_2_PI,
+                "0.63661977236758134307553505349005744813783858296183",
+            ),
+            (
+                f128::FRAC_2_SQRT_PI,
+                "1.1283791670955125738961589031215451716881012586580",
+            ),
+            (
+                f128::SQRT_2,
+                "1.4142135623730950488016887242096980785696718753769",
+            ),
+            (
+                f128::FRAC_1_SQRT_2,
+                "0.70710678118654752440084436210484903928483593768847",
+            ),
+            (
+                f128::SQRT_3,
+                "1.7320508075688772935274463415058723669428052538104",
+            ),
+            (
+                f128::LN_2,
+                "0.69314718055994530941723212145817656807550013436026",
+            ),
+            (
+                f128::LN_10,
+                "2.3025850929940456840179914546843642076011014886288",
+            ),
+            (
+                f128::LN_PI,
+                "1.1447298858494001741434273513530587116472948129153",
+            ),
+            (
+                f128::LOG2_E,
+                "1.4426950408889634073599246810018921374266459541530",
+            ),
+            (
+                f128::LOG10_E,
+                "0.43429448190325182765112891891660508229439700580367",
+            ),
+            (
+                f128::LOG2_10,
+                "3.3219280948873623478703194294893901758648313930246",
+            ),
+            (
+                f128::LOG10_2,
+                "0.30102999566398119521373889472449302676818988146211",
+            ),
+            (
+                f128::EGAMMA,
+                "0.57721566490153286060651209008240243104215933593992",
+            ),
+            (
+                f128::CATALAN,
+                "0.91596559417721901505460351493238411077414937428167",
+            ),
+            (
+                f128::PHI,
+                "1.6180339887498948482045868343656381177203091798058",
+            ),
+        ];
+        for &(x, digits) in &table {
+            assert_eq!(bits(x), bits(f128::from_str(digits).unwrap()), "{}", digits);
+        }
+
+        let methods: [(fn() -> f128, f128); 19] = [
+            (FloatConst::E, f128::E),
+            (FloatConst::FRAC_1_PI, f128::FRAC_1_PI),
+            (FloatConst::FRAC_1_SQRT_2, f128::FRAC_1_SQRT_2),
+            (FloatConst::FRAC_2_PI, f128::FRAC_2_PI),
+            (FloatConst::FRAC_2_SQRT_PI, f128::FRAC_2_SQRT_PI),
+            (FloatConst::FRAC_PI_2, f128::FRAC_PI_2),
+            (FloatConst::FRAC_PI_3, f128::FRAC_PI_3),
+            (FloatConst::FRAC_PI_4, f128::FRAC_PI_4),
+            (FloatConst::FRAC_PI_6, f128::FRAC_PI_6),
+            (FloatConst::FRAC_PI_8, f128::FRAC_PI_8),
+            (FloatConst::LN_10, f128::LN_10),
+            (FloatConst::LN_2, f128::LN_2),
+            (FloatConst::LOG10_E, f128::LOG10_E),
+            (FloatConst::LOG2_E, f128::LOG2_E),
+            (FloatConst::PI, f128::PI),
+            (FloatConst::SQRT_2, f128::SQRT_2),
+            (FloatConst::TAU, f128::TAU),
+            (FloatConst::LOG10_2, f128::LOG10_2),
+            (FloatConst::LOG2_10, f128::LOG2_10),
+        ];
+        for &(method, x) in &methods {
+            assert_eq!(bits(method()), bits(x));
+        }
+    }
+
+    #[test]
+    fn test_literal_macro() {
+        const PI: f128 = f128!(3.14159265358979323846264338327950288);
+        static HALF: f128 = f128!("0x.8");
+        assert_eq!(bits(PI), bits(f128::PI));
+        assert_eq!(bits(HALF), bits(f128::from_f64(0.5).unwrap()));
+
+        let cases = [
+            (f128!(0), "0"),
+            (f128!(-0.0), "-0"),
+            (f128!(12), "12"),
+            (f128!(0x10), "16"),
+            (f128!(1_000.5), "1000.5"),
+            (f128!(1.), "1"),
+            (f128!(-2.5e-3), "-0.0025"),
+            (f128!(25E+2), "2500"),
+            (f128!("0x1.8p3"), "12"),
+            (f128!("-0X1P-2"), "-0.25"),
+            (f128!("+.5"), "0.5"),
+            (f128!("-inf"), "-inf"),
+            (f128!("Infinity"), "inf"),
+            (f128!(0.1), "0.1"),
+            // 2^113 + 1 and 2^113 + 3 lie halfway between two f128s and round to even.
+            (
+                f128!(10384593717069655257060992658440193),
+                "10384593717069655257060992658440192",
+            ),
+            (
+                f128!(10384593717069655257060992658440195),
+                "10384593717069655257060992658440196",
+            ),
+            (
+                f128!(1.18973149535723176508575932662800702e4932),
+                "1.18973149535723176508575932662800702e4932",
+            ),
+            (
+                f128!(3.36210314311209350626267781732175260e-4932),
+                "3.36210314311209350626267781732175260e-4932",
+            ),
+            (
+                f128!(6.475175119438025110924438958227646552e-4966),
+                "6.475175119438025110924438958227646552e-4966",
+            ),
+            (f128!(3.3e-4966), "3.3e-4966"),
+            (
+                f128!("0x1.ffffffffffffffffffffffffffffp+16383"),
+                "0x1.ffffffffffffffffffffffffffffp+16383",
+            ),
+            (f128!("0x1.00000000000000000000000000008"), "1"),
+            (
+                f128!("0x1.000000000000000000000000000080000001"),
+                "0x1.0000000000000000000000000001",
+            ),
+            (
+                f128!("0x0.000000000000000000000000000000000000000000001p-16314"),
+                "0x1p-16494",
+            ),
+        ];
+        for &(x, s) in &cases {
+            assert_eq!(bits(x), bits(s.parse::<f128>().unwrap()), "{}", s);
+        }
+        assert!(f128!("nan").is_nan());
+
+        // The conversion also runs outside constants, which checks it against `FromStr` on the
+        // strings formatting produces.
+        let mut state = 0x9e37_79b9_7f4a_7c15;
+        for _ in 0..300 {
+            let x = random_operand(&mut state);
+            if x.is_nan() {
+                continue;
+            }
+            for s in &[
+                format!("{:e}", x),
+                format!("{:.60e}", x),
+                format!("{:#x}", x),
+            ] {
+                let expected = s.parse::<f128>().unwrap();
+                assert_eq!(
+                    bits(f128::__from_literal(s, false)),
+                    bits(expected),
+                    "{}",
+                    s
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_total_order() {
+        use std::cmp::Ordering;
+        use std::collections::{BTreeMap, HashSet};
+
+        let quiet = f128::NAN;
+        let signaling = f128::from_bits(0x7fff_4000_0000_0000_0000_0000_0000_0000);
+        let subnormal = f128::from_bits(1);
+        // In ascending totalOrder.
+        let ordered = [
+            -quiet,
+            -signaling,
+            f128::NEG_INFINITY,
+            f128!(-2.5),
+            -subnormal,
+            f128!(-0.0),
+            f128!(0),
+            subnormal,
+            f128!(1),
+            f128!(2.5),
+            f128::INFINITY,
+            signaling,
+            quiet,
+        ];
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(a.total_cmp(b), i.cmp(&j), "{:?} {:?}", a, b);
+            }
+        }
+
+        let mut values: Vec<TotalF128> = ordered.iter().rev().map(|&x| TotalF128(x)).collect();
+        values.sort();
+        for (x, y) in values.iter().zip(ordered.iter()) {
+            assert_eq!(bits(x.0), bits(*y));
+        }
+        assert_eq!(values.binary_search(&TotalF128(f128!(1))), Ok(8));
+        assert_eq!(values.binary_search(&TotalF128(f128!(1.5))), Err(9));
+
+        let mut map = BTreeMap::new();
+        map.insert(TotalF128(f128!(0)), "zero");
+        map.insert(TotalF128(f128!(-0.0)), "negative zero");
+        map.insert(TotalF128(quiet), "nan");
+        assert_eq!(map[&TotalF128(quiet)], "nan");
+        assert_eq!(map.keys().next(), Some(&TotalF128(f128!(-0.0))));
+
+        let set: HashSet<TotalF128> = ordered.iter().map(|&x| TotalF128(x)).collect();
+        assert_eq!(set.len(), ordered.len());
+        assert!(set.contains(&TotalF128(quiet)));
+        assert!(!set.contains(&TotalF128(f128!(3))));
+        assert_eq!(TotalF128(quiet).cmp(&TotalF128(quiet)), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_min_max() {
+        let nan = f128::NAN;
+        let snan = f128::from_bits(0x7fff_4000_0000_0000_0000_0000_0000_0000);
+        let (one, two) = (f128!(1), f128!(2));
+        let (zero, neg_zero) = (f128!(0), f128!(-0.0));
+        let same = |x: f128, y: f128| bits(x) == bits(y) || x.is_nan() && y.is_nan();
+
+        assert!(same(Float::max(-one, -two), -one));
+        assert!(same(Float::min(-one, -two), -two));
+        assert!(same(Float::max(nan, -two), -two));
+        assert!(same(Float::min(one, nan), one));
+        assert!(Float::max(nan, nan).is_nan());
+
+        // (x, y, maximum, minimum, maximum_number, minimum_number, max_magnitude, min_magnitude)
+        let table = [
+            (one, two, two, one, two, one, two, one),
+            (-one, -two, -one, -two, -one, -two, -two, -one),
+            (-two, one, one, -two, one, -two, -two, one),
+            (-one, one, one, -one, one, -one, one, -one),
+            (
+                zero, neg_zero, zero, neg_zero, zero, neg_zero, zero, neg_zero,
+            ),
+            (
+                neg_zero, zero, zero, neg_zero, zero, neg_zero, zero, neg_zero,
+            ),
+            (nan, one, nan, nan, one, one, nan, nan),
+            (-two, nan, nan, nan, -two, -two, nan, nan),
+            (nan, nan, nan, nan, nan, nan, nan, nan),
+            (
+                f128::INFINITY,
+                -one,
+                f128::INFINITY,
+                -one,
+                f128::INFINITY,
+                -one,
+                f128::INFINITY,
+                -one,
+            ),
+        ];
+        for &(x, y, max, min, max_num, min_num, max_mag, min_mag) in &table {
+            assert!(same(x.maximum(y), max), "maximum({:?}, {:?})", x, y);
+            assert!(same(x.minimum(y), min), "minimum({:?}, {:?})", x, y);
+            assert!(
+                same(x.maximum_number(y), max_num),
+                "maximum_number({:?}, {:?})",
+                x,
+                y
+            );
+            assert!(
+                same(x.minimum_number(y), min_num),
+                "minimum_number({:?}, {:?})",
+                x,
+                y
+            );
+            assert!(
+                same(x.max_magnitude(y), max_mag),
+                "max_magnitude({:?}, {:?})",
+                x,
+                y
+            );
+            assert!(
+                same(x.min_magnitude(y), min_mag),
+                "min_magnitude({:?}, {:?})",
+                x,
+                y
+            );
+        }
+
+        // Signalling NaNs are quieted, and raise the invalid exception even where they lose.
+        let (x, raised) = with_flags(|| snan.maximum(one));
+        assert!(x.is_nan() && !soft::is_signaling(x));
+        assert_eq!(raised, FpFlags::INVALID);
+        let (x, raised) = with_flags(|| one.minimum_number(snan));
+        assert_eq!(bits(x), bits(one));
+        assert_eq!(raised, FpFlags::INVALID);
+        let (_, raised) = with_flags(|| one.maximum_number(nan));
+        assert_eq!(raised, FpFlags::empty());
+
+        assert_eq!(bits(f128!(3).clamp(one, two)), bits(two));
+        assert_eq!(bits(f128!(-3).clamp(one, two)), bits(one));
+        assert_eq!(bits(f128!(1.5).clamp(one, two)), bits(f128!(1.5)));
+        assert_eq!(bits(neg_zero.clamp(zero, one)), bits(zero));
+        assert_eq!(bits(zero.clamp(-one, neg_zero)), bits(neg_zero));
+        assert!(nan.clamp(one, two).is_nan());
+        assert!(std::panic::catch_unwind(|| one.clamp(two, one)).is_err());
+        assert!(std::panic::catch_unwind(|| one.clamp(nan, one)).is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::collections::{HashMap, HashSet};
+        use std::hash::{Hash, Hasher};
+
+        fn hash<T: Hash>(x: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            x.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        fn hash_slice(xs: &[f128]) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            Hash::hash_slice(xs, &mut hasher);
+            hasher.finish()
+        }
+
+        let other_nan = f128::from_bits(0xffff_8000_0000_0000_0000_0000_0000_0001);
+        assert_eq!(hash(&f128!(0)), hash(&f128!(-0.0)));
+        assert_eq!(hash(&f128::NAN), hash(&other_nan));
+        assert_ne!(hash(&f128!(1)), hash(&f128!(-1)));
+        assert_eq!(
+            hash_slice(&[f128!(0), f128!(1)]),
+            hash_slice(&[f128!(-0.0), f128!(1)])
+        );
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..1_000 {
+            let x = random_operand(&mut state);
+            let y = -x;
+            if !x.is_nan() {
+                assert_eq!(x == y, hash(&x) == hash(&y), "{:?}", x);
+            }
+        }
+
+        let mut map = HashMap::new();
+        map.insert(BitHash(f128!(0)), "zero");
+        map.insert(BitHash(f128!(-0.0)), "negative zero");
+        map.insert(BitHash(f128::NAN), "nan");
+        assert_eq!(map[&BitHash(f128!(0))], "zero");
+        assert_eq!(map[&BitHash(f128!(-0.0))], "negative zero");
+        assert_eq!(map[&BitHash(f128::NAN)], "nan");
+        assert_eq!(map.get(&BitHash(other_nan)), None);
+
+        assert_ne!(BitHash(f128!(0)), BitHash(f128!(-0.0)));
+        assert_ne!(hash(&BitHash(f128::NAN)), hash(&BitHash(other_nan)));
+        let set: HashSet<BitHash> = [f128!(0), f128!(-0.0), f128::NAN, other_nan, f128::NAN]
+            .iter()
+            .map(|&x| BitHash(x))
+            .collect();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains(&BitHash(f128::NAN)));
+    }
+
+    /// Runs the `Float` methods on values f64 can represent and compares the results, rounded to
+    /// f64, with std's. Operations that are exact in both types must agree to the bit; the others
+    /// may differ by a few units in the last place of the f64, which std's math library does not
+    /// always round correctly.
+    #[test]
+    fn test_float_conformance() {
+        fn same(x: f128, y: f64) -> bool {
+            let x = x.to_f64().unwrap();
+            x.to_bits() == y.to_bits() || x.is_nan() && y.is_nan()
+        }
+        fn close(x: f128, y: f64) -> bool {
+            let x = x.to_f64().unwrap();
+            if x.is_nan() || y.is_nan() || x.is_infinite() || y.is_infinite() || x == 0.0 {
+                return same(f128::from_f64(x).unwrap(), y);
+            }
+            x.is_sign_negative() == y.is_sign_negative()
+                && (x.to_bits() as i64 - y.to_bits() as i64).abs() <= 4
+        }
+
+        let inputs = [
+            0.0,
+            -0.0,
+            0.1,
+            -0.3,
+            0.5,
+            -0.5,
+            0.75,
+            0.999,
+            1.0,
+            -1.0,
+            1.5,
+            2.0,
+            2.5,
+            -2.5,
+            3.7,
+            -3.7,
+            10.0,
+            -100.0,
+            123456.789,
+            -1e-5,
+            1e-300,
+            1e300,
+            5e-324,
+            f64::MAX,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ];
+        macro_rules! unary {
+            ($check:ident: $($method:ident)*) => ($(
+                for &x in &inputs {
+                    let result = Float::$method(f128::from_f64(x).unwrap());
+                    assert!($check(result, Float::$method(x)), "{}({:e}) = {:e}", stringify!($method), x, result);
+                }
+            )*)
+        }
+        unary!(same: floor ceil round trunc fract abs signum recip sqrt);
+        // f64's subnormals are normal f128s, so each class is checked with a value of its own.
+        let classes = [
+            (f128::ZERO, 0.0),
+            (f128::NEG_ZERO, -0.0),
+            (f128::MIN_POSITIVE_SUBNORMAL, 5e-324),
+            (-f128::MIN_POSITIVE / f128::from_u8(2).unwrap(), -f64::MIN_POSITIVE / 2.0),
+            (f128::MIN_POSITIVE, f64::MIN_POSITIVE),
+            (-f128::ONE, -1.0),
+            (f128::MAX, f64::MAX),
+            (f128::INFINITY, f64::INFINITY),
+            (f128::NEG_INFINITY, f64::NEG_INFINITY),
+            (f128::NAN, f64::NAN),
+        ];
+        for &(x, y) in &classes {
+            assert_eq!(Float::classify(x), y.classify(), "classify({:e})", y);
+            assert_eq!(Float::is_normal(x), y.is_normal(), "is_normal({:e})", y);
+        }
+        unary!(close: exp exp2 exp_m1 ln log2 log10 ln_1p cbrt to_degrees to_radians);
+        unary!(close: sin cos tan asin acos atan sinh cosh tanh atanh);
+        // std's asinh and acosh overflow in an intermediate step for arguments near f64::MAX.
+        for &x in inputs.iter().filter(|x| !(x.abs() > 1e300)) {
+            let y = f128::from_f64(x).unwrap();
+            assert!(close(y.asinh(), x.asinh()), "asinh({:e})", x);
+            assert!(close(y.acosh(), x.acosh()), "acosh({:e})", x);
+        }
+        let ln_2_max = f64::MAX.ln() + 2f64.ln();
+        assert!(close(f128::from_f64(f64::MAX).unwrap().asinh(), ln_2_max));
+        assert!(close(f128::from_f64(f64::MAX).unwrap().acosh(), ln_2_max));
+
+        for &x in &inputs {
+            let y = f128::from_f64(x).unwrap();
+            let (sin, cos) = y.sin_cos();
+            assert!(
+                close(sin, x.sin()) && close(cos, x.cos()),
+                "sin_cos({:e})",
+                x
+            );
+            assert_eq!(y.is_nan(), x.is_nan(), "{:e}", x);
+            assert_eq!(y.is_infinite(), x.is_infinite(), "{:e}", x);
+            assert_eq!(y.is_finite(), x.is_finite(), "{:e}", x);
+            assert_eq!(y.is_sign_negative(), x.is_sign_negative(), "{:e}", x);
+            assert_eq!(y.is_sign_positive(), x.is_sign_positive(), "{:e}", x);
+            assert_eq!(Float::is_sign_negative(y), y.to_bits() >> 127 == 1);
+            // std's powi multiplies repeatedly, rounding each time, so compare with powf.
+            for n in &[-31, -5, -2, -1, 0, 1, 2, 3, 5, 31, 1000] {
+                let result = y.powi(*n);
+                assert!(
+                    close(result, x.powf(*n as f64)),
+                    "powi({:e}, {}) = {:e}",
+                    x,
+                    n,
+                    result
+                );
+            }
+            for &z in &inputs {
+                let w = f128::from_f64(z).unwrap();
+                assert!(
+                    same(y.abs_sub(w), Float::abs_sub(x, z)),
+                    "abs_sub({:e}, {:e})",
+                    x,
+                    z
+                );
+                assert!(
+                    same(y.mul_add(w, w), x.mul_add(z, z)),
+                    "mul_add({:e}, {:e})",
+                    x,
+                    z
+                );
+                assert!(close(y.powf(w), x.powf(z)), "powf({:e}, {:e})", x, z);
+                assert!(close(y.hypot(w), x.hypot(z)), "hypot({:e}, {:e})", x, z);
+                assert!(close(y.atan2(w), x.atan2(z)), "atan2({:e}, {:e})", x, z);
+                assert!(close(y.log(w), x.log(z)), "log({:e}, {:e})", x, z);
+                if x != 0.0 || z != 0.0 {
+                    assert!(same(Float::max(y, w), x.max(z)), "max({:e}, {:e})", x, z);
+                    assert!(same(Float::min(y, w), x.min(z)), "min({:e}, {:e})", x, z);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_cmp() {
         let a = f128::parse("1.5").unwrap();
@@ -163,4 +1566,1033 @@ mod tests {
         assert!(b >= a);
         assert!(a != b);
     }
+
+    fn bits(x: f128) -> u128 {
+        x.to_bits()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let one = f128::ONE;
+        let two = f128::TWO;
+        let three = f128::from_u8(3).unwrap();
+        let third = one / three;
+        assert_eq!(bits(one + one), bits(two));
+        assert_eq!(bits(three - two), bits(one));
+        assert_eq!(bits(two * two), bits(f128::from_u8(4).unwrap()));
+        assert_eq!(bits(third), bits(f128::parse("0.333333333333333333333333333333333333333").unwrap()));
+        assert_eq!(bits(third * three), bits(one));
+
+        // Ties round to even: 1 + 2^-113 is exactly halfway between 1 and its successor.
+        let half_ulp = f128::from_bits(0x3f8e << 112);
+        assert_eq!(bits(one + half_ulp), bits(one));
+        let next = f128::from_bits(bits(one) + 1);
+        assert_eq!(bits(next + half_ulp), bits(one) + 2);
+
+        // Signed zeros.
+        assert_eq!(bits(f128::ZERO + f128::NEG_ZERO), bits(f128::ZERO));
+        assert_eq!(bits(f128::NEG_ZERO + f128::NEG_ZERO), bits(f128::NEG_ZERO));
+        assert_eq!(bits(one - one), bits(f128::ZERO));
+        assert_eq!(bits(-one * f128::ZERO), bits(f128::NEG_ZERO));
+
+        // Overflow, underflow and subnormals.
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert!((max * two).is_infinite());
+        assert_eq!(bits(max + one), bits(max));
+        let tiny = f128::from_bits(1);
+        assert_eq!(bits(tiny + tiny), 2);
+        assert_eq!(bits(tiny / two), 0);
+        assert_eq!(bits(f128::from_bits(3) / two), 2);
+        let min_normal = f128::from_bits(1 << 112);
+        assert_eq!(bits(min_normal - tiny), (1 << 112) - 1);
+
+        // Invalid operations and infinities.
+        assert!((f128::INFINITY - f128::INFINITY).is_nan());
+        assert!((f128::INFINITY * f128::ZERO).is_nan());
+        assert!((f128::ZERO / f128::ZERO).is_nan());
+        assert_eq!(bits(one / f128::NEG_ZERO), bits(f128::NEG_INFINITY));
+        assert_eq!(bits(one / f128::INFINITY), bits(f128::ZERO));
+    }
+
+    #[test]
+    fn test_comparison_special_values() {
+        let nan = f128::NAN;
+        let one = f128::ONE;
+        assert!(nan != nan);
+        assert!(!(nan < one) && !(nan > one) && !(nan <= one) && !(nan >= one));
+        assert_eq!(nan.partial_cmp(&one), None);
+        assert!(f128::ZERO == f128::NEG_ZERO);
+        assert!(-one < f128::NEG_ZERO);
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert!(f128::NEG_INFINITY < -max);
+        assert!(max < f128::INFINITY);
+    }
+
+    #[test]
+    fn test_rounding_conversions() {
+        // 2^113 + 1 is halfway between two binary128 values and rounds to even.
+        let n = (1u128 << 113) + 1;
+        assert_eq!(f128::from_u128(n).unwrap().to_u128().unwrap(), 1 << 113);
+        assert_eq!(f128::from_u128(n + 2).unwrap().to_u128().unwrap(), (1 << 113) + 4);
+        assert_eq!(f128::from_u128(u128::MAX).unwrap().exp_bits(), 16383 + 128);
+        assert_eq!(f128::from_i128(i128::MIN).unwrap().to_i128().unwrap(), i128::MIN);
+        assert_eq!(f128::from_i64(i64::MIN).unwrap().to_i64().unwrap(), i64::MIN);
+        assert_eq!(f128::from_u64(u64::MAX).unwrap().to_u64().unwrap(), u64::MAX);
+        assert_eq!(f128::parse("-2.9").unwrap().to_i32().unwrap(), -2);
+
+        for &x in &[0.1f64, -1.5e300, 5e-324, f64::MAX, f64::MIN_POSITIVE, -0.0] {
+            let y = f128::from_f64(x).unwrap();
+            assert_eq!(y.to_f64().unwrap().to_bits(), x.to_bits());
+            assert_eq!(y.to_f32().unwrap().to_bits(), (x as f32).to_bits());
+        }
+        assert!(f128::from_f32(std::f32::NAN).unwrap().is_nan());
+        assert!(f128::NAN.to_f64().unwrap().is_nan());
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert_eq!(max.to_f64().unwrap(), f64::INFINITY);
+        // Double rounding through f64 would give the wrong answer here.
+        let x = f128::from_bits(bits(f128::ONE) + (1 << 88) + 1);
+        assert_eq!(x.to_f32().unwrap(), 1.0 + std::f32::EPSILON);
+    }
+
+    // Unless `soft-float` leaves it out, libf128 is compiled with the C shims even when
+    // `native-f128` replaces them, so the other backends can be checked against them directly.
+    #[cfg(not(feature = "soft-float"))]
+    mod c_backend {
+        use f128_t::f128;
+
+        #[link(name = "f128", kind = "static")]
+        extern "C" {
+            pub fn f128_add(x: f128, y: f128) -> f128;
+            pub fn f128_sub(x: f128, y: f128) -> f128;
+            pub fn f128_mul(x: f128, y: f128) -> f128;
+            pub fn f128_div(x: f128, y: f128) -> f128;
+            pub fn lteq(lhs: f128, rhs: f128) -> u8;
+            pub fn eqq(lhs: f128, rhs: f128) -> u8;
+            pub fn u128_to_f128(x: u128) -> f128;
+            pub fn i64_to_f128(x: i64) -> f128;
+            pub fn f128_to_i64(x: f128) -> i64;
+            pub fn f128_to_f64(x: f128) -> f64;
+            pub fn f128_to_f32(x: f128) -> f32;
+            pub fn f64_to_f128(x: f64) -> f128;
+        }
+    }
+
+    /// The next number from a xorshift generator, which is all the randomness the tests need.
+    fn next_u64(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    /// Operands that hit every special case: zeros, subnormals, normals near both ends of the
+    /// exponent range, infinities and NaNs with varied payloads, combined with random fractions.
+    fn random_operand(state: &mut u64) -> f128 {
+        let mut next = || next_u64(state);
+        let frac = ((next() as u128) << 64 | next() as u128) >> (16 + next() % 3 * 50);
+        let exp: u128 = match next() % 8 {
+            0 => 0,
+            1 => 0x7fff,
+            2 => 1 + next() as u128 % 4,
+            3 => 0x7ffe - next() as u128 % 4,
+            4 => 0x3fff + next() as u128 % 8 - 4,
+            _ => next() as u128 % 0x7fff,
+        };
+        let sign = (next() as u128 & 1) << 127;
+        f128::from_bits(sign | exp << 112 | frac)
+    }
+
+    #[cfg(not(feature = "soft-float"))]
+    #[test]
+    fn test_backend_matches_c() {
+        #[cfg(feature = "native-f128")]
+        use native as backend;
+        #[cfg(not(feature = "native-f128"))]
+        use soft as backend;
+
+        let mut state = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..200_000 {
+            let a = random_operand(&mut state);
+            let b = random_operand(&mut state);
+            unsafe {
+                let sum = backend::f128_add(a, b);
+                assert_eq!(bits(sum), bits(c_backend::f128_add(a, b)), "{:?} + {:?}", a, b);
+                let difference = backend::f128_sub(a, b);
+                assert_eq!(bits(difference), bits(c_backend::f128_sub(a, b)), "{:?} - {:?}", a, b);
+                let product = backend::f128_mul(a, b);
+                assert_eq!(bits(product), bits(c_backend::f128_mul(a, b)), "{:?} * {:?}", a, b);
+                let quotient = backend::f128_div(a, b);
+                assert_eq!(bits(quotient), bits(c_backend::f128_div(a, b)), "{:?} / {:?}", a, b);
+                assert_eq!(backend::lteq(a, b), c_backend::lteq(a, b));
+                assert_eq!(backend::eqq(a, b), c_backend::eqq(a, b));
+                assert_eq!(
+                    backend::f128_to_f64(a).to_bits(),
+                    c_backend::f128_to_f64(a).to_bits()
+                );
+                assert_eq!(
+                    backend::f128_to_f32(a).to_bits(),
+                    c_backend::f128_to_f32(a).to_bits()
+                );
+                if a.abs() < f128::from_u64(1 << 62).unwrap() {
+                    assert_eq!(backend::f128_to_i64(a), c_backend::f128_to_i64(a));
+                }
+
+                let n = bits(b) >> (bits(a) % 128);
+                assert_eq!(bits(backend::u128_to_f128(n)), bits(c_backend::u128_to_f128(n)));
+                let i = bits(b) as i64;
+                assert_eq!(bits(backend::i64_to_f128(i)), bits(c_backend::i64_to_f128(i)));
+                let d = f64::from_bits(bits(b) as u64);
+                assert_eq!(bits(backend::f64_to_f128(d)), bits(c_backend::f64_to_f128(d)));
+            }
+        }
+    }
+
+    /// The Rust versions of the libquadmath functions that `soft-float` builds with: the exact
+    /// ones bit for bit, the elementary functions to within libquadmath's own error of a few ulps.
+    #[cfg(not(any(feature = "soft-float", feature = "native-f128")))]
+    #[test]
+    fn test_soft_math_matches_c() {
+        use soft::math;
+
+        type Unary = (fn(f128) -> f128, unsafe extern "C" fn(f128) -> f128);
+        type Binary = (fn(f128, f128) -> f128, unsafe extern "C" fn(f128, f128) -> f128);
+        let same = |x: f128, y: f128| x.is_nan() && y.is_nan() || bits(x) == bits(y);
+        let close = |x: f128, y: f128| {
+            same(x, y) || !x.is_nan() && !y.is_nan() && x.ulps_between(y) <= 8
+        };
+        let moderate = |x: f128, e: u128| {
+            f128::from_bits(bits(x) & !(0x7fff << 112) | e << 112)
+        };
+        let half = f128::ONE / f128::TWO;
+        let three = f128::from_u8(3).unwrap();
+        let special = [
+            f128::ZERO, f128::NEG_ZERO, f128::ONE, -f128::ONE, half, -half, f128::TWO, -f128::TWO,
+            three, -three, three * half, -three * half, f128::INFINITY, f128::NEG_INFINITY,
+            f128::NAN, f128::from_bits(1),
+        ];
+        let mut state = 0x3c6e_f372_fe94_f82b;
+        for i in 0..20_000 {
+            let a = random_operand(&mut state);
+            let b = random_operand(&mut state);
+            let c = random_operand(&mut state);
+            let pick = |n: u128| special[n as usize % 16];
+            let (a, b, c) = match i % 4 {
+                // Magnitudes between 2^-16 and 2^16.
+                0 | 2 => {
+                    let (e, f) = (0x3fef + bits(c) % 32, 0x3fef + bits(c) / 32 % 32);
+                    (moderate(a, e), moderate(b, f), c)
+                }
+                // Zeros, infinities, NaN and small integers and halves, alone or with another.
+                1 => (pick(bits(c)), pick(bits(c) >> 4), pick(bits(c) >> 8)),
+                _ if i % 8 == 3 => (pick(bits(c)), b, c),
+                _ => (a, b, c),
+            };
+            unsafe {
+                let exact: [Unary; 7] = [
+                    (math::floorq_f, floorq_f),
+                    (math::ceilq_f, ceilq_f),
+                    (math::truncq_f, truncq_f),
+                    (math::roundq_f, roundq_f),
+                    (math::nearbyintq_f, nearbyintq_f),
+                    (math::rintq_f, rintq_f),
+                    (math::logbq_f, logbq_f),
+                ];
+                for (k, &(ours, theirs)) in exact.iter().enumerate() {
+                    assert!(same(ours(a), theirs(a)), "function {} of {:?}", k, a);
+                }
+                let exact2: [Binary; 7] = [
+                    (math::fmodq_f, fmodq_f),
+                    (math::remainderq_f, remainderq_f),
+                    (math::copysignq_f, copysignq_f),
+                    (math::fdimq_f, fdimq_f),
+                    (math::fmaxq_f, fmaxq_f),
+                    (math::fminq_f, fminq_f),
+                    (math::nextafterq_f, nextafterq_f),
+                ];
+                for (k, &(ours, theirs)) in exact2.iter().enumerate() {
+                    assert!(same(ours(a, b), theirs(a, b)), "function {} of {:?}, {:?}", k, a, b);
+                }
+                let fma = math::fmaq_f(a, b, c);
+                assert!(same(fma, fmaq_f(a, b, c)), "fma({:?}, {:?}, {:?})", a, b, c);
+
+                let (mut ours, mut theirs) = (0, 0);
+                let remainder = math::remquoq_f(a, b, &mut ours);
+                assert!(same(remainder, remquoq_f(a, b, &mut theirs)));
+                assert_eq!(ours.signum(), theirs.signum(), "remquo({:?}, {:?})", a, b);
+                assert_eq!(ours % 8, theirs % 8, "remquo({:?}, {:?})", a, b);
+                let (mut ours, mut theirs) = (f128::ZERO, f128::ZERO);
+                assert!(same(math::modfq_f(a, &mut ours), modfq_f(a, &mut theirs)));
+                assert!(same(ours, theirs));
+                if a.is_finite() {
+                    let (mut ours, mut theirs) = (0, 0);
+                    assert!(same(math::frexpq_f(a, &mut ours), frexpq_f(a, &mut theirs)));
+                    assert_eq!(ours, theirs);
+                    let n = (bits(b) % 40_000) as i32 - 20_000;
+                    assert!(same(math::scalbnq_f(a, n), scalbnq_f(a, n)));
+                }
+                if a.is_finite() && a != f128::ZERO {
+                    assert_eq!(math::ilogbq_f(a), ilogbq_f(a));
+                }
+                if a.abs() < f128::from_u64(1 << 62).unwrap() {
+                    assert_eq!(math::llrintq_f(a), llrintq_f(a));
+                    assert_eq!(math::llroundq_f(a), llroundq_f(a));
+                }
+
+                let elementary: [Unary; 20] = [
+                    (math::expq_f, expq_f),
+                    (math::exp2q_f, exp2q_f),
+                    (math::expm1q_f, expm1q_f),
+                    (math::logq_f, logq_f),
+                    (math::log2q_f, log2q_f),
+                    (math::log10q_f, log10q_f),
+                    (math::log1pq_f, log1pq_f),
+                    (math::cbrtq_f, cbrtq_f),
+                    (math::sinq_f, sinq_f),
+                    (math::cosq_f, cosq_f),
+                    (math::tanq_f, tanq_f),
+                    (math::asinq_f, asinq_f),
+                    (math::acosq_f, acosq_f),
+                    (math::atanq_f, atanq_f),
+                    (math::sinhq_f, sinhq_f),
+                    (math::coshq_f, coshq_f),
+                    (math::tanhq_f, tanhq_f),
+                    (math::asinhq_f, asinhq_f),
+                    (math::acoshq_f, acoshq_f),
+                    (math::atanhq_f, atanhq_f),
+                ];
+                for (k, &(ours, theirs)) in elementary.iter().enumerate() {
+                    let (x, y) = (ours(a), theirs(a));
+                    assert!(
+                        close(x, y),
+                        "function {} of {:x}: {:x}, not {:x}",
+                        k, bits(a), bits(x), bits(y)
+                    );
+                }
+                let elementary2: [Binary; 3] = [
+                    (math::powq_f, powq_f),
+                    (math::hypotq_f, hypotq_f),
+                    (math::atan2q_f, atan2q_f),
+                ];
+                for (k, &(ours, theirs)) in elementary2.iter().enumerate() {
+                    let (x, y) = (ours(a, b), theirs(a, b));
+                    assert!(
+                        close(x, y),
+                        "function {} of {:x}, {:x}: {:x}, not {:x}",
+                        k, bits(a), bits(b), bits(x), bits(y)
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "native-f128")]
+    #[test]
+    fn test_native_conversions() {
+        use native::Native as native;
+
+        let x: native = 1.0 / 3.0;
+        let y: f128 = x.into();
+        assert_eq!(bits(y), x.to_bits());
+        assert_eq!(native::from(y).to_bits(), x.to_bits());
+        assert_eq!(bits(y), bits(f128::ONE / f128::from_u8(3).unwrap()));
+        assert!(native::from(f128::NAN).is_nan());
+        assert_eq!(native::from(f128::NEG_ZERO).to_bits(), bits(f128::NEG_ZERO));
+    }
+
+    #[test]
+    fn test_rounding_guard() {
+        assert_eq!(RoundingMode::current(), RoundingMode::NearestEven);
+        with_rounding(RoundingMode::Up, || {
+            assert_eq!(RoundingMode::current(), RoundingMode::Up);
+            {
+                let _guard = RoundingGuard::new(RoundingMode::Down);
+                assert_eq!(RoundingMode::current(), RoundingMode::Down);
+            }
+            assert_eq!(RoundingMode::current(), RoundingMode::Up);
+        });
+        assert_eq!(RoundingMode::current(), RoundingMode::NearestEven);
+
+        let result = std::panic::catch_unwind(|| {
+            with_rounding(RoundingMode::TowardZero, || panic!("unwinding"))
+        });
+        assert!(result.is_err());
+        assert_eq!(RoundingMode::current(), RoundingMode::NearestEven);
+
+        // The mode is per thread.
+        with_rounding(RoundingMode::Down, || {
+            let other = std::thread::spawn(RoundingMode::current).join().unwrap();
+            assert_eq!(other, RoundingMode::NearestEven);
+        });
+    }
+
+    #[test]
+    fn test_directed_rounding() {
+        use RoundingMode::*;
+        let one = f128::ONE;
+        let tiny = f128::from_bits(1);
+        let next = bits(one) + 1;
+        let third = |mode| with_rounding(mode, || one / f128::from_u8(3).unwrap());
+
+        assert_eq!(with_rounding(Up, || bits(one + tiny)), next);
+        assert_eq!(with_rounding(Down, || bits(one + tiny)), bits(one));
+        assert_eq!(with_rounding(TowardZero, || bits(one + tiny)), bits(one));
+        assert_eq!(with_rounding(Down, || bits(-one - tiny)), next | 1 << 127);
+        assert_eq!(with_rounding(TowardZero, || bits(-one - tiny)), bits(-one));
+        assert_eq!(bits(third(Up)), bits(third(Down)) + 1);
+        assert_eq!(bits(third(TowardZero)), bits(third(Down)));
+        assert_eq!(bits(third(NearestEven)), bits(third(Down)));
+        assert_eq!(with_rounding(Up, || bits(one * one)), bits(one));
+
+        // Exact zeros and overflow.
+        assert_eq!(with_rounding(Down, || bits(one - one)), bits(f128::NEG_ZERO));
+        assert_eq!(with_rounding(Up, || bits(one - one)), bits(f128::ZERO));
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert_eq!(with_rounding(TowardZero, || bits(max * f128::TWO)), bits(max));
+        assert_eq!(with_rounding(Down, || bits(max + max)), bits(max));
+        assert!(with_rounding(Up, || max + max).is_infinite());
+        assert_eq!(with_rounding(Up, || bits(-max * f128::TWO)), bits(-max));
+
+        // Conversions.
+        let big = with_rounding(Down, || f128::from_u128(u128::MAX).unwrap());
+        assert_eq!(big.to_u128().unwrap(), u128::MAX - (1 << 15) + 1);
+        assert_eq!(with_rounding(Up, || f128::from_u128(u128::MAX).unwrap()).exp_bits(), 16383 + 128);
+        let near_min = |mode| with_rounding(mode, || f128::from_i128(i128::MIN + 1).unwrap());
+        assert_eq!(bits(near_min(Down)), bits(f128::from_i128(i128::MIN).unwrap()));
+        assert_eq!(near_min(Up).to_i128().unwrap(), i128::MIN + (1 << 14));
+        let (down, up) = (third(Down), third(Up));
+        // The nearest f64 to 1/3 lies below it.
+        let third_f64 = (1.0f64 / 3.0).to_bits();
+        assert_eq!(with_rounding(Up, || down.to_f64().unwrap()).to_bits(), third_f64 + 1);
+        assert_eq!(with_rounding(Down, || up.to_f64().unwrap()).to_bits(), third_f64);
+        let third_f32 = (1.0f32 / 3.0).to_bits();
+        assert_eq!(with_rounding(Up, || third(NearestEven).to_f32().unwrap()).to_bits(), third_f32);
+        assert_eq!(with_rounding(Down, || third(NearestEven).to_f32().unwrap()).to_bits(), third_f32 - 1);
+    }
+
+    /// Maps the bits of a non-NaN value to an integer with the same ordering, so that adjacent
+    /// values differ by one.
+    fn ordered(x: f128) -> i128 {
+        let b = bits(x);
+        if b >> 127 == 0 {
+            b as i128
+        } else {
+            -((b & !(1 << 127)) as i128)
+        }
+    }
+
+    #[test]
+    fn test_directed_rounding_brackets() {
+        use RoundingMode::*;
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = || next_u64(&mut state);
+        for _ in 0..20_000 {
+            let exp = [1, 0x3ff0 + next() % 32, 0x7ffe - next() % 2, next() % 0x7fff][next() as usize % 4];
+            let a = f128::from_bits((next() as u128) << 64 | next() as u128 & !(0x7fff << 112) | (exp as u128) << 112);
+            let b = f128::from_bits((next() as u128) << 64 | next() as u128);
+            if a.is_nan() || b.is_nan() {
+                continue;
+            }
+            let ops: [fn(f128, f128) -> f128; 5] = [
+                |a, b| a + b,
+                |a, b| a - b,
+                |a, b| a * b,
+                |a, b| a / b,
+                |a, _| a.abs().sqrt(),
+            ];
+            for op in &ops {
+                let [n, z, u, d] = [NearestEven, TowardZero, Up, Down]
+                    .map(|m| with_rounding(m, || op(a, b)));
+                if n.is_nan() {
+                    assert!(z.is_nan() && u.is_nan() && d.is_nan());
+                    continue;
+                }
+                let (n, z, u, d) = (ordered(n), ordered(z), ordered(u), ordered(d));
+                assert!(u == d || u == d + 1 || (u == 0 && d == 0), "{:?} {:?}", a, b);
+                assert!(d <= n && n <= u);
+                assert_eq!(z, if u > 0 { d } else { u });
+            }
+        }
+    }
+
+    #[test]
+    fn test_sqrt_rounding() {
+        use RoundingMode::*;
+        // Correctly rounded results, checked with mpmath, that libquadmath's sqrtq gets wrong.
+        for &(x, root) in &[
+            (
+                0x368e_ec83_972c_97b6_ef8f_972c_97b6_678e,
+                0x3b46_f629_99e7_2572_aea4_181e_8b6d_4d87,
+            ),
+            (
+                0x0001_047f_b3e4_426b_ca54_652a_c6f8_193b,
+                0x2000_023d_57e8_ce08_b3cf_bab8_e613_fe37,
+            ),
+            (0x0000_0000_0000_0000_0000_0000_0000_0002, 0x1fc8_6a09_e667_f3bc_c908_b2fb_1366_ea95),
+        ] {
+            assert_eq!(bits(soft::sqrt(f128::from_bits(x), NearestEven)), root);
+            assert_eq!(bits(f128::from_bits(x).sqrt()), root);
+        }
+
+        // Rounding down and up must bracket the exact root: d^2 <= x <= u^2. Since x is
+        // representable, d^2 <= x exactly when d * d rounded up is <= x, and likewise for u.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        for _ in 0..20_000 {
+            let r = next_u64(&mut state);
+            let x = f128::from_bits(((r as u128) << 64 | r as u128 >> 3) >> (1 + r % 4));
+            let (d, u) = (with_rounding(Down, || x.sqrt()), with_rounding(Up, || x.sqrt()));
+            assert!(with_rounding(Up, || d * d) <= x, "{:?}", x);
+            assert!(with_rounding(Down, || u * u) >= x, "{:?}", x);
+            assert!(bits(u) == bits(d) || bits(u) == bits(d) + 1);
+            let n = x.sqrt();
+            assert!(bits(n) == bits(d) || bits(n) == bits(u), "{:?}", x);
+        }
+
+        assert!((-f128::ONE).sqrt().is_nan());
+        assert_eq!(bits(f128::NEG_ZERO.sqrt()), bits(f128::NEG_ZERO));
+        assert!(f128::INFINITY.sqrt().is_infinite());
+        assert_eq!(with_rounding(Up, || bits(f128::from_u8(4).unwrap().sqrt())), bits(f128::TWO));
+    }
+
+    #[test]
+    fn test_exception_flags() {
+        use std::convert::TryFrom;
+        let one = f128::ONE;
+        let three = f128::from_u8(3).unwrap();
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        let tiny = f128::from_bits(0x0001_0000_0000_0000_0000_0000_0000_0000);
+        let snan = f128::from_bits(0x7fff_4000_0000_0000_0000_0000_0000_0000);
+        let flags_of = |f: &dyn Fn() -> f128| with_flags(f).1;
+
+        // Nothing is recorded unless asked for.
+        let _ = one / three;
+        assert!(FpFlags::current().is_empty());
+
+        let x: f128 = 0.1f64.into();
+        let y: f128 = 0.3f64.into();
+        assert!(flags_of(&|| x * y).is_empty());
+        assert_eq!(flags_of(&|| one / three), FpFlags::INEXACT);
+        assert_eq!(flags_of(&|| max * three), FpFlags::OVERFLOW | FpFlags::INEXACT);
+        assert_eq!(flags_of(&|| tiny / three), FpFlags::UNDERFLOW | FpFlags::INEXACT);
+        assert!(flags_of(&|| tiny / f128::TWO).is_empty());
+        assert_eq!(flags_of(&|| one / f128::ZERO), FpFlags::DIV_BY_ZERO);
+        assert_eq!(flags_of(&|| f128::ZERO / f128::ZERO), FpFlags::INVALID);
+        assert_eq!(flags_of(&|| f128::INFINITY - f128::INFINITY), FpFlags::INVALID);
+        assert_eq!(flags_of(&|| (-one).sqrt()), FpFlags::INVALID);
+        assert_eq!(flags_of(&|| f128::TWO.sqrt()), FpFlags::INEXACT);
+        assert!(flags_of(&|| f128::NAN + one).is_empty());
+        assert_eq!(flags_of(&|| snan + one), FpFlags::INVALID);
+        assert_eq!(flags_of(&|| f128::from_u128(u128::MAX).unwrap()), FpFlags::INEXACT);
+
+        let (third, flags) = with_flags(|| (one / three).to_f64().unwrap());
+        assert_eq!(third, 1.0 / 3.0);
+        assert_eq!(flags, FpFlags::INEXACT);
+        assert_eq!(with_flags(|| max.to_f32()).1, FpFlags::OVERFLOW | FpFlags::INEXACT);
+
+        assert_eq!(with_flags(|| f128::NAN < one).1, FpFlags::INVALID);
+        assert!(with_flags(|| f128::NAN == one).1.is_empty());
+        assert_eq!(with_flags(|| snan == one).1, FpFlags::INVALID);
+
+        let parse = |s| with_flags(|| f128::parse(s).unwrap());
+        assert_eq!(parse("1e-5000").1, FpFlags::UNDERFLOW | FpFlags::INEXACT);
+        assert_eq!(parse("-0x1p-20000").1, FpFlags::UNDERFLOW | FpFlags::INEXACT);
+        assert_eq!(parse("1e5000").1, FpFlags::OVERFLOW | FpFlags::INEXACT);
+        assert!(parse("0.000e-9999").1.is_empty());
+        assert!(parse("-inf").1.is_empty());
+        // Results that are tiny and inexact underflow even when they are not zero.
+        assert_eq!(parse("1e-4940").1, FpFlags::UNDERFLOW | FpFlags::INEXACT);
+        assert_eq!(parse("-0x1.8p-16494 ").1, FpFlags::UNDERFLOW | FpFlags::INEXACT);
+        assert!(parse("0x1p-16494").1.is_empty());
+        assert!(parse("0x1.8p-16493xyz").1.is_empty());
+        assert_eq!(parse("0.1e").1, FpFlags::INEXACT);
+        assert!(parse("1.5e+").1.is_empty());
+
+        // Conversions to integers that fail are invalid.
+        assert_eq!(with_flags(|| f128::NAN.to_i32()).1, FpFlags::INVALID);
+        assert_eq!(with_flags(|| max.to_u64()).1, FpFlags::INVALID);
+        assert_eq!(with_flags(|| (-one).to_u8()).1, FpFlags::INVALID);
+        assert_eq!(with_flags(|| i8::try_from(f128::from_u8(128).unwrap())).1, FpFlags::INVALID);
+        assert_eq!(with_flags(|| u128::try_from(f128::NEG_INFINITY)).1, FpFlags::INVALID);
+        assert!(with_flags(|| f128::parse("-0.5").unwrap().to_u8()).1.is_empty());
+        assert!(with_flags(|| i32::try_from(f128::parse("2.5").unwrap())).1.is_empty());
+
+        // Flags are sticky and nested calls report to the enclosing ones.
+        FpFlags::clear();
+        let (inner, outer) = with_flags(|| {
+            let _ = one / f128::ZERO;
+            with_flags(|| one / three).1
+        });
+        assert_eq!(inner, FpFlags::INEXACT);
+        assert_eq!(outer, FpFlags::DIV_BY_ZERO | FpFlags::INEXACT);
+        assert_eq!(FpFlags::take(), FpFlags::DIV_BY_ZERO | FpFlags::INEXACT);
+        assert!(FpFlags::current().is_empty());
+
+        // A panic leaves the outer flags raised, with those raised before it, and stops recording.
+        let result = std::panic::catch_unwind(|| {
+            with_flags(|| {
+                let _ = one / f128::ZERO;
+                with_flags(|| {
+                    let _ = one / three;
+                    panic!("unwinding")
+                })
+            })
+        });
+        assert!(result.is_err());
+        assert!(!flags::recording());
+        assert_eq!(FpFlags::take(), FpFlags::DIV_BY_ZERO | FpFlags::INEXACT);
+
+        {
+            let _guard = FlagsGuard::new();
+            let _ = with_rounding(RoundingMode::Up, || one / three);
+            assert!(FpFlags::test(FpFlags::INEXACT | FpFlags::OVERFLOW));
+            assert!(!FpFlags::test(FpFlags::OVERFLOW));
+            FpFlags::clear();
+            let _ = one + one;
+            assert!(FpFlags::current().is_empty());
+        }
+        let _ = one / three;
+        assert!(FpFlags::current().is_empty());
+    }
+
+    #[test]
+    fn test_decode() {
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert_eq!(f128::ONE.decode(), (false, -112, 1 << 112));
+        assert_eq!(
+            (-f128::from_f64(0.75).unwrap()).decode(),
+            (true, -113, 3 << 111)
+        );
+        assert_eq!(max.decode(), (false, 16271, (1 << 113) - 1));
+        assert_eq!(f128::from_bits(1).decode(), (false, -16494, 1));
+        assert_eq!(f128::NEG_ZERO.decode(), (true, -16494, 0));
+        assert_eq!(f128::INFINITY.decode(), (false, 16272, 1 << 112));
+
+        let mut state = 0x853c_49e6_748f_ea9bu64;
+        for _ in 0..10_000 {
+            let x = random_operand(&mut state);
+            if x.is_finite() {
+                let (negative, exponent, significand) = x.decode();
+                assert_eq!(
+                    bits(f128::from_parts(negative, exponent, significand)),
+                    bits(x)
+                );
+            }
+        }
+
+        // Significands wider than 113 bits are rounded, ties to even.
+        let two_114 = f128::from_parts(false, 114, 1);
+        assert_eq!(
+            bits(f128::from_parts(false, 0, (1 << 114) + 1)),
+            bits(two_114)
+        );
+        assert_eq!(
+            bits(f128::from_parts(false, 0, (1 << 114) + 2)),
+            bits(two_114)
+        );
+        assert_eq!(
+            bits(f128::from_parts(false, 0, (1 << 114) + 6)),
+            bits(two_114) + 2
+        );
+        assert_eq!(
+            f128::from_parts(true, -3, u128::MAX),
+            -f128::from_parts(false, 125, 1)
+        );
+        assert_eq!(
+            with_rounding(RoundingMode::TowardZero, || bits(f128::from_parts(
+                false,
+                0,
+                u128::MAX
+            ))),
+            bits(f128::from_parts(false, 15, (1 << 113) - 1))
+        );
+        assert_eq!(bits(f128::from_parts(false, -16495, 3)), 2);
+        assert_eq!(
+            bits(f128::from_parts(true, i32::MIN, 1)),
+            bits(f128::NEG_ZERO)
+        );
+        assert!(f128::from_parts(false, i32::MAX, 1).is_infinite());
+        assert_eq!(
+            bits(f128::from_parts(false, 16271, (1 << 113) - 1)),
+            bits(max)
+        );
+        assert_eq!(
+            with_flags(|| f128::from_parts(false, -20000, 1)).1,
+            FpFlags::UNDERFLOW | FpFlags::INEXACT
+        );
+
+        assert_eq!(Float::integer_decode(f128::ONE), (1 << 63, -63, 1));
+        assert_eq!(
+            Float::integer_decode(-f128::from_u8(6).unwrap()),
+            (3 << 62, -61, -1)
+        );
+        assert_eq!(
+            Float::integer_decode(f128::from_bits(1)),
+            (1, -16494, 1)
+        );
+        assert_eq!(Float::integer_decode(max), (u64::MAX, 16271 + 49, 1));
+        assert_eq!(
+            num_traits::float::FloatCore::integer_decode(f128::from_f64(1e300).unwrap()),
+            {
+                let (m, e, s) = Float::integer_decode(1e300f64);
+                (m << 11, e - 11, s)
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        let parse = |s: &str, radix| f128::from_str_radix(s, radix).unwrap();
+        let int = |n: i32| f128::from_i32(n).unwrap();
+
+        assert_eq!(parse("101.011", 2), int(43) / int(8));
+        assert_eq!(parse("-ff.8", 16), -int(511) / int(2));
+        assert_eq!(parse("1e5", 16), int(0x1e5));
+        assert_eq!(parse("1.8p-3", 16), int(3) / int(16));
+        assert_eq!(parse("12.5e-1", 10), int(5) / int(4));
+        assert_eq!(parse("+10e2", 3), int(27));
+        assert_eq!(parse("Zz", 36), int(35 * 36 + 35));
+        assert_eq!(bits(parse("0.1", 3)), bits(f128::ONE / int(3)));
+        assert_eq!(bits(parse("0.1", 7)), bits(f128::ONE / int(7)));
+        assert_eq!(bits(parse("-0.00", 10)), bits(f128::NEG_ZERO));
+        assert!(parse("-Infinity", 5).is_infinite() && parse("-inf", 5) < f128::ZERO);
+        assert!(parse("NaN", 36).is_nan());
+        assert!(parse("1e5000", 10).is_infinite());
+        assert_eq!(bits(parse("-1e-5000", 10)), bits(f128::NEG_ZERO));
+        assert_eq!(bits(parse("1p-16494", 8)), 1);
+        assert_eq!(bits(parse("0.1p-16493", 2)), 1);
+        assert_eq!(bits(parse("1p-16495", 2)), 0);
+        assert_eq!(bits(parse("11p-16496", 2)), 1);
+
+        // 1 + 2^-113 is exactly halfway between 1 and its successor and rounds to even; any
+        // further non-zero digit decides it.
+        let half = "1.00000000000000000000000000000000009629649721936179265279889712924636592690508241076940976199693977832794189453125";
+        assert_eq!(bits(parse(half, 10)), bits(f128::ONE));
+        assert_eq!(
+            bits(parse(&format!("{}0001", half), 10)),
+            bits(f128::ONE) + 1
+        );
+        assert_eq!(bits(parse(&half[..half.len() - 1], 10)), bits(f128::ONE));
+
+        // Digits past the first 128 bits are only compared with the point where the result
+        // changes, if one lies close enough to matter, so long inputs take linear time.
+        let zeros = "0".repeat(400_000);
+        assert_eq!(bits(parse(&format!("{}{}", half, zeros), 10)), bits(f128::ONE));
+        assert_eq!(
+            bits(parse(&format!("{}{}1", half, zeros), 10)),
+            bits(f128::ONE) + 1
+        );
+        let ninth = format!("0.{}", "1".repeat(400_000));
+        assert_eq!(bits(parse(&ninth, 10)), bits(f128::ONE / int(9)));
+        // 0.111... in radix 3 approaches 1/2 from below.
+        let below_half = format!("0.{}", "1".repeat(100_000));
+        let down = with_rounding(RoundingMode::Down, || with_flags(|| parse(&below_half, 3)));
+        assert_eq!((bits(down.0) + 1, down.1), (bits(f128::ONE / int(2)), FpFlags::INEXACT));
+        let up = with_rounding(RoundingMode::Up, || parse(&below_half, 3));
+        assert_eq!(bits(up), bits(f128::ONE / int(2)));
+        // 2^-150 written out exactly takes 105 digits, and raises nothing.
+        let pow = "700649232162408535461864791644958065640130970938257885878534141944895541342930300743319094181060791015625";
+        let exact = with_flags(|| parse(&format!("{}e-150", pow), 10));
+        assert_eq!((bits(exact.0), exact.1), (16_233 << 112, FpFlags::empty()));
+
+        let third = "0.33333333333333333333333333333333333333333333";
+        let up = with_rounding(RoundingMode::Up, || parse(third, 10));
+        let down = with_rounding(RoundingMode::Down, || parse(third, 10));
+        assert_eq!(bits(up), bits(down) + 1);
+        assert_eq!(bits(parse(third, 10)), bits(f128::ONE / int(3)));
+
+        // Decimal input agrees with libquadmath's strtoflt128.
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        let mut next = || next_u64(&mut state);
+        for _ in 0..1_000 {
+            let digits = format!("{}{}", next(), next());
+            let len = 1 + next() as usize % digits.len();
+            let point = next() as usize % (len + 1);
+            let exp = (next() % 9_900) as i32 - 4_950;
+            let s = format!("{}.{}e{}", &digits[..point], &digits[point..len], exp);
+            assert_eq!(bits(parse(&s, 10)), bits(f128::parse(&s).unwrap()), "{}", s);
+        }
+
+        use ParseF128ErrorKind::*;
+        let error = |s: &str, radix| *f128::from_str_radix(s, radix).unwrap_err().kind();
+        assert_eq!(error("", 10), Empty);
+        assert_eq!(error("-", 10), Empty);
+        assert_eq!(error(".", 10), InvalidDigit { position: 1 });
+        assert_eq!(error("1.2.3", 10), TrailingCharacters { position: 3 });
+        assert_eq!(error("12e", 10), InvalidDigit { position: 3 });
+        assert_eq!(error("12e+", 10), InvalidDigit { position: 4 });
+        assert_eq!(error("1e5x", 10), TrailingCharacters { position: 3 });
+        assert_eq!(error("102", 2), TrailingCharacters { position: 2 });
+        assert_eq!(error("1x3", 30), TrailingCharacters { position: 1 });
+        assert_eq!(error(" 1", 10), InvalidDigit { position: 0 });
+    }
+
+    #[test]
+    fn test_hex_float() {
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        let three_halves = f128::from_f64(1.5).unwrap();
+        assert_eq!(
+            f128::PI.to_hex_string(),
+            "0x1.921fb54442d18469898cc51701b8p+1"
+        );
+        assert_eq!(f128::ONE.to_hex_string(), "0x1p+0");
+        assert_eq!((-three_halves).to_hex_string(), "-0x1.8p+0");
+        assert_eq!(f128::NEG_ZERO.to_hex_string(), "-0x0p+0");
+        assert_eq!(f128::from_bits(1).to_hex_string(), "0x1p-16494");
+        assert_eq!(
+            max.to_hex_string(),
+            "0x1.ffffffffffffffffffffffffffffp+16383"
+        );
+        assert_eq!(f128::NEG_INFINITY.to_hex_string(), "-inf");
+        assert_eq!(f128::NAN.to_hex_string(), "nan");
+
+        assert_eq!(format!("{:x}", three_halves), "1.8p+0");
+        assert_eq!(
+            format!("{:#X}", f128::PI),
+            "0x1.921FB54442D18469898CC51701B8P+1"
+        );
+        assert_eq!(format!("{:+.3x}", three_halves), "+1.800p+0");
+        assert_eq!(format!("{:.0x}", three_halves), "1p+1");
+        assert_eq!(format!("{:.4x}", f128::PI), "1.9220p+1");
+        assert_eq!(
+            format!("{:.1x}", f128::from_f64(1.96875).unwrap()),
+            "1.0p+1"
+        );
+        assert_eq!(format!("{:#012x}", -f128::ONE), "-0x000001p+0");
+        assert_eq!(format!("{:>8x}", f128::ONE), "    1p+0");
+        assert_eq!(format!("{:.2x}", f128::ZERO), "0.00p+0");
+        assert_eq!(format!("{:<6X}|", f128::INFINITY), "INF   |");
+
+        let mut state = 0x6a09_e667_f3bc_c908u64;
+        for _ in 0..10_000 {
+            let x = random_operand(&mut state);
+            let parsed = f128::from_hex_str(&x.to_hex_string()).unwrap();
+            if x.is_nan() {
+                assert!(parsed.is_nan());
+            } else {
+                assert_eq!(bits(parsed), bits(x), "{}", x.to_hex_string());
+            }
+        }
+
+        let hex = |s| f128::from_hex_str(s).unwrap();
+        assert_eq!(
+            bits(hex("1.921fb54442d18469898cc51701b8p1")),
+            bits(f128::PI)
+        );
+        assert_eq!(bits(hex("+0X1P-16494")), 1);
+        assert_eq!(bits(hex("-0x.8p+1")), bits(-f128::ONE));
+        // Digits beyond the 28 that fit round to nearest, ties to even.
+        assert_eq!(
+            bits(hex("0x1.00000000000000000000000000008p0")),
+            bits(f128::ONE)
+        );
+        assert_eq!(
+            bits(hex("0x1.00000000000000000000000000018p0")),
+            bits(f128::ONE) + 2
+        );
+        assert_eq!(
+            bits(hex("0x1.000000000000000000000000000081p0")),
+            bits(f128::ONE) + 1
+        );
+        assert!(hex("0x1p16384").is_infinite());
+
+        use ParseF128ErrorKind::*;
+        let error = |s| *f128::from_hex_str(s).unwrap_err().kind();
+        assert_eq!(error("0x"), InvalidDigit { position: 2 });
+        assert_eq!(error("-0xinf"), InvalidDigit { position: 3 });
+        assert_eq!(error("0x1.8q1"), TrailingCharacters { position: 5 });
+        assert_eq!(error("0x1p"), InvalidDigit { position: 4 });
+        assert_eq!(error(""), Empty);
+    }
+
+    #[test]
+    fn test_decimal_formatting() {
+        let f = |x: f64| f128::from_f64(x).unwrap();
+        assert_eq!(format!("{:.2}", f(-0.0)), "-0.00");
+        assert_eq!(format!("{:>8.1}", f(3.25159)), "     3.3");
+        assert_eq!(format!("{:<8}|", f(2.5)), "2.5     |");
+        assert_eq!(format!("{:*^9}", f(1.5)), "***1.5***");
+        assert_eq!(format!("{:+}", f(1.0)), "+1");
+        assert_eq!(format!("{:+.0}", f(2.5)), "+2");
+        assert_eq!(format!("{:.1} {:.1}", f(0.25), f(0.35)), "0.2 0.3");
+        assert_eq!(format!("{:08.2}", f(-1.234)), "-0001.23");
+        assert_eq!(format!("{:.3}", f(1e-10)), "0.000");
+        assert_eq!(format!("{:.2}", f(0.005)), "0.01");
+        assert_eq!(format!("{:.1}", f(9.96)), "10.0");
+        assert_eq!(format!("{:^9.1e}", f(1234.5)), "  1.2e3  ");
+        assert_eq!(format!("{:+08.2e}", f(12.5)), "+01.25e1");
+        assert_eq!(format!("{:.0e}", f(2.5e10)), "2e10");
+        assert_eq!(format!("{:.3e}", f(9.9996)), "1.000e1");
+        assert_eq!(
+            format!("{:e} {:E}", f(0.375), f(-0.375)),
+            "3.75e-1 -3.75E-1"
+        );
+        assert_eq!(
+            format!("{:e} {:.2e}", f128::ZERO, -f128::ZERO),
+            "0e0 -0.00e0"
+        );
+        assert_eq!(
+            format!("{:+} {:05}", f128::NAN, f128::NEG_INFINITY),
+            "NaN -0inf"
+        );
+        assert_eq!(
+            format!("{:?} {:e} {:E}", f128::NAN, -f128::NAN, f128::NAN),
+            format!("{:?} {:e} {:E}", f64::NAN, -f64::NAN, f64::NAN)
+        );
+        assert_eq!(format!("{:>6e}", f128::INFINITY), "   inf");
+
+        let third = f128::ONE / f128::from_u8(3).unwrap();
+        assert_eq!(
+            format!("{:.36}", third),
+            "0.333333333333333333333333333333333317"
+        );
+        assert_eq!(
+            format!("{:.35e}", third),
+            "3.33333333333333333333333333333333317e-1"
+        );
+        let two_113 = f128::from_u128(1 << 113).unwrap();
+        assert_eq!(
+            format!("{:.0}", two_113),
+            "10384593717069655257060992658440192"
+        );
+        assert_eq!(
+            format!("{:.34e}", two_113),
+            "1.0384593717069655257060992658440192e34"
+        );
+        let tiny = f128::from_bits(1);
+        assert_eq!(
+            format!("{:.35e}", tiny),
+            "6.47517511943802511092443895822764655e-4966"
+        );
+        assert_eq!(format!("{:.3}", tiny), "0.000");
+        assert_eq!(format!("{:.4966}", tiny).len(), 4968);
+        assert!(format!("{:.4966}", tiny).ends_with("00006"));
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert_eq!(format!("{:.0}", max).len(), 4933);
+        assert_eq!(format!("{:.1e}", max), "1.2e4932");
+
+        // Formatting the exact value, the same digits as `f64` itself writes.
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        for i in 0..2000 {
+            let x = f64::from_bits(next_u64(&mut state));
+            if !x.is_finite() {
+                continue;
+            }
+            let p = i % 40;
+            assert_eq!(format!("{:.*e}", p, f(x)), format!("{:.*e}", p, x));
+            if x.abs() < 1e30 {
+                assert_eq!(format!("{:.*}", p, f(x)), format!("{:.*}", p, x));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shortest_formatting() {
+        let parse = |s: &str| f128::from_str(s).unwrap();
+        assert_eq!(parse("0.1").to_string(), "0.1");
+        assert_eq!(parse("-123.456e-7").to_string(), "-0.0000123456");
+        assert_eq!(parse("1e40").to_string(), format!("1{}", "0".repeat(40)));
+        assert_eq!(format!("{:e}", parse("0.1")), "1e-1");
+        assert_eq!(format!("{:E}", parse("123456")), "1.23456E5");
+        assert_eq!(format!("{:>+10}", parse("2.5")), "      +2.5");
+        let third = f128::ONE / f128::from_u8(3).unwrap();
+        assert_eq!(third.to_string(), "0.3333333333333333333333333333333333");
+        assert_eq!(format!("{:e}", f128::from_bits(1)), "6e-4966");
+        let max = f128::from_bits(0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff);
+        assert_eq!(
+            format!("{:e}", max),
+            "1.189731495357231765085759326628007e4932"
+        );
+        // At a power of two the gap below is half the gap above.
+        let min_normal = f128::from_bits(1 << 112);
+        assert_eq!(
+            format!("{:e}", min_normal),
+            "3.3621031431120935062626778173217526e-4932"
+        );
+
+        assert_eq!(format!("{:?}", f128::ONE), "1.0");
+        assert_eq!(format!("{:?}", -f128::ZERO), "-0.0");
+        assert_eq!(format!("{:?}", parse("1e15")), "1000000000000000.0");
+        assert_eq!(format!("{:?}", parse("1e16")), "1e16");
+        assert_eq!(format!("{:?}", parse("0.0001")), "0.0001");
+        assert_eq!(format!("{:?}", parse("0.00001")), "1e-5");
+        assert_eq!(format!("{:.2?}", f128::ONE), "1.00");
+        assert_eq!(format!("{:?}", f128::NEG_INFINITY), "-inf");
+
+        // Every value comes back unchanged from each form, through both parsers.
+        let mut state = 0x9e37_79b9_7f4a_7c15_u64;
+        for _ in 0..200 {
+            let x = random_operand(&mut state);
+            if x.is_nan() {
+                continue;
+            }
+            for s in &[x.to_string(), format!("{:e}", x), format!("{:?}", x)] {
+                assert_eq!(bits(parse(s)), bits(x), "{}", s);
+                assert_eq!(bits(f128::parse(s).unwrap()), bits(x), "{}", s);
+            }
+            // One significant digit fewer is not enough.
+            let digits = format!("{:e}", x.abs())
+                .split('e')
+                .next()
+                .unwrap()
+                .replace('.', "")
+                .len();
+            if digits > 1 && x.is_finite() {
+                let shorter = format!("{:.*e}", digits - 2, x);
+                assert_ne!(bits(parse(&shorter)), bits(x), "{}", shorter);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_str() {
+        use std::convert::TryFrom;
+        let int = |n: i32| f128::from_i32(n).unwrap();
+
+        assert_eq!(bits("1.5".parse::<f128>().unwrap()), bits(int(3) / int(2)));
+        assert_eq!(
+            bits("-2.5e-3".parse::<f128>().unwrap()),
+            bits(-int(5) / int(2000))
+        );
+        assert_eq!(bits(f128::from_str("+.5E1").unwrap()), bits(int(5)));
+        assert_eq!(bits(f128::try_from("7.").unwrap()), bits(int(7)));
+        assert_eq!(
+            bits("0x1.8p-3".parse::<f128>().unwrap()),
+            bits(int(3) / int(16))
+        );
+        assert_eq!(bits("-0.0".parse::<f128>().unwrap()), bits(f128::NEG_ZERO));
+        assert_eq!(bits("0e99999".parse::<f128>().unwrap()), 0);
+        assert!("-INFINITY".parse::<f128>().unwrap().is_infinite());
+        assert!("nan".parse::<f128>().unwrap().is_nan());
+        let pi = "3.1415926535897932384626433832795028841971693993751058";
+        assert_eq!(bits(pi.parse::<f128>().unwrap()), bits(f128::PI));
+        // Subnormal results are fine, only total underflow is an error.
+        assert_eq!(bits("6.5e-4966".parse::<f128>().unwrap()), 1);
+        // Rounding down instead of to infinity is not an overflow.
+        assert_eq!(
+            with_rounding(RoundingMode::TowardZero, || bits(
+                "1e5000".parse::<f128>().unwrap()
+            )),
+            0x7ffe_ffff_ffff_ffff_ffff_ffff_ffff_ffff
+        );
+
+        use ParseF128ErrorKind::*;
+        let error = |s: &str| *s.parse::<f128>().unwrap_err().kind();
+        assert_eq!(error(""), Empty);
+        assert_eq!(error("+"), Empty);
+        assert_eq!(error("abc"), InvalidDigit { position: 0 });
+        assert_eq!(error("-.e5"), InvalidDigit { position: 2 });
+        assert_eq!(error("1.5xyz"), TrailingCharacters { position: 3 });
+        assert_eq!(error("1.5 "), TrailingCharacters { position: 3 });
+        assert_eq!(error("1e"), InvalidDigit { position: 2 });
+        assert_eq!(error("1e-"), InvalidDigit { position: 3 });
+        assert_eq!(error("0x"), InvalidDigit { position: 2 });
+        assert_eq!(error("0x1e5p"), InvalidDigit { position: 6 });
+        assert_eq!(error("1e5000"), Overflow);
+        assert_eq!(error("-0x1p16384"), Overflow);
+        assert_eq!(error("1e-5000"), Underflow);
+        assert_eq!(error("infinite"), InvalidDigit { position: 0 });
+        assert_eq!(
+            f128::try_from("1,5").unwrap_err().to_string(),
+            "unexpected characters after f128 literal at position 1"
+        );
+    }
 }
@@ -0,0 +1,546 @@
+//! The `f128!` macro, which turns a literal into an `f128` at compile time.
+//!
+//! Everything here is a `const fn`, so it uses fixed-size integers instead of `bignum::Big`, and
+//! reports bad literals by panicking, which fails the compilation of the constant.
+
+use f128_t::f128;
+
+/// Creates an `f128` from a literal at compile time, correctly rounded to nearest with ties to
+/// even whatever the current `RoundingMode`.
+///
+/// Decimal literals may be written as Rust numbers or as strings; hexadecimal floats, which Rust
+/// has no literals for, as strings in the syntax `FromStr` accepts. Strings may also hold a sign,
+/// `inf`, `infinity` or `nan`. The result is a constant expression, so it can initialize
+/// `const` and `static` items.
+///
+/// ```
+/// # #[macro_use] extern crate f128;
+/// # use f128::f128;
+/// const PI: f128 = f128!(3.14159265358979323846264338327950288);
+/// assert_eq!(PI, f128::PI);
+/// assert_eq!(f128!("0x1.8p3"), f128!(12));
+/// assert_eq!(f128!(-2.5e-3), f128!("-0.0025"));
+/// ```
+///
+/// Literals that are malformed, that round to infinity or to zero, or that have more than 1000
+/// significant decimal digits (leading and trailing zeros aside) do not compile:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate f128;
+/// let x = f128!(1.5f64);
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate f128;
+/// let x = f128!(1e5000);
+/// ```
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate f128;
+/// let x = f128!("0x1p-20000");
+/// ```
+#[macro_export]
+macro_rules! f128 {
+    (- $literal:literal) => {{
+        const VALUE: $crate::f128 = $crate::f128::__from_literal(stringify!($literal), true);
+        VALUE
+    }};
+    ($literal:literal) => {{
+        const VALUE: $crate::f128 = $crate::f128::__from_literal(stringify!($literal), false);
+        VALUE
+    }};
+}
+
+/// Significant decimal digits a literal may have. The `f128!` docs and the error for longer
+/// literals state the number too.
+const MAX_DIGITS: usize = 1000;
+
+/// Limbs in the fixed-size integers: enough for the power of ten that scales the smallest
+/// literal with the most digits, times a 127-bit quotient.
+const LIMBS: usize = 640;
+
+/// Exponents are clamped to this magnitude while they are read; anything beyond it is out of
+/// range regardless.
+const EXP_LIMIT: i64 = 100_000;
+
+/// An unsigned integer stored as `len` little-endian 32-bit limbs.
+struct Big {
+    limbs: [u32; LIMBS],
+    len: usize,
+}
+
+impl Big {
+    const fn from_u32(x: u32) -> Big {
+        let mut b = Big {
+            limbs: [0; LIMBS],
+            len: 0,
+        };
+        if x != 0 {
+            b.limbs[0] = x;
+            b.len = 1;
+        }
+        b
+    }
+
+    const fn is_zero(&self) -> bool {
+        self.len == 0
+    }
+
+    const fn bit_len(&self) -> usize {
+        if self.len == 0 {
+            0
+        } else {
+            32 * self.len - self.limbs[self.len - 1].leading_zeros() as usize
+        }
+    }
+
+    const fn bit(&self, i: usize) -> bool {
+        i / 32 < self.len && self.limbs[i / 32] >> (i % 32) & 1 != 0
+    }
+
+    /// `self * m + a`.
+    const fn mul_add(&mut self, m: u32, a: u32) {
+        let mut carry = a as u64;
+        let mut i = 0;
+        while i < self.len {
+            let t = self.limbs[i] as u64 * m as u64 + carry;
+            self.limbs[i] = t as u32;
+            carry = t >> 32;
+            i += 1;
+        }
+        if carry != 0 {
+            self.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    const fn mul_pow10(&mut self, mut n: u64) {
+        while n >= 9 {
+            self.mul_add(1_000_000_000, 0);
+            n -= 9;
+        }
+        self.mul_add(10u32.pow(n as u32), 0);
+    }
+
+    const fn shl(&mut self, bits: usize) {
+        if self.len == 0 {
+            return;
+        }
+        let (words, bits) = (bits / 32, (bits % 32) as u32);
+        if self.len + words + 1 > LIMBS {
+            panic!("f128 literal has too many digits");
+        }
+        let mut i = self.len + words + 1;
+        while i > words {
+            i -= 1;
+            let src = i - words;
+            let high = if src < self.len { self.limbs[src] } else { 0 };
+            let low = if bits != 0 && src > 0 {
+                self.limbs[src - 1] >> (32 - bits)
+            } else {
+                0
+            };
+            self.limbs[i] = if bits == 0 { high } else { high << bits | low };
+        }
+        while i > 0 {
+            i -= 1;
+            self.limbs[i] = 0;
+        }
+        self.len += words + 1;
+        self.trim();
+    }
+
+    const fn shr(&mut self, bits: usize) {
+        let (words, bits) = (bits / 32, (bits % 32) as u32);
+        if words >= self.len {
+            self.len = 0;
+            return;
+        }
+        let mut i = 0;
+        while i + words < self.len {
+            let low = self.limbs[i + words] >> bits;
+            let high = if bits != 0 && i + words + 1 < self.len {
+                self.limbs[i + words + 1] << (32 - bits)
+            } else {
+                0
+            };
+            self.limbs[i] = low | high;
+            i += 1;
+        }
+        while i < self.len {
+            self.limbs[i] = 0;
+            i += 1;
+        }
+        self.len -= words;
+        self.trim();
+    }
+
+    /// Whether `self >= other`.
+    const fn ge(&self, other: &Big) -> bool {
+        if self.len != other.len {
+            return self.len > other.len;
+        }
+        let mut i = self.len;
+        while i > 0 {
+            i -= 1;
+            if self.limbs[i] != other.limbs[i] {
+                return self.limbs[i] > other.limbs[i];
+            }
+        }
+        true
+    }
+
+    /// `self -= other`, which must not be larger than `self`.
+    const fn sub(&mut self, other: &Big) {
+        let mut borrow = 0;
+        let mut i = 0;
+        while i < self.len {
+            let o = if i < other.len { other.limbs[i] } else { 0 } as u64;
+            let t = (self.limbs[i] as u64).wrapping_sub(o + borrow);
+            self.limbs[i] = t as u32;
+            borrow = t >> 63;
+            i += 1;
+        }
+        self.trim();
+    }
+
+    const fn push(&mut self, limb: u32) {
+        if self.len == LIMBS {
+            panic!("f128 literal has too many digits");
+        }
+        self.limbs[self.len] = limb;
+        self.len += 1;
+    }
+
+    const fn trim(&mut self) {
+        while self.len > 0 && self.limbs[self.len - 1] == 0 {
+            self.len -= 1;
+        }
+    }
+
+    /// The bits of `self` from bit `n` up, which must fit in a `u128`.
+    const fn bits_from(&self, n: usize) -> u128 {
+        let mut x = 0;
+        let mut i = self.bit_len();
+        while i > n {
+            i -= 1;
+            x = x << 1 | self.bit(i) as u128;
+        }
+        x
+    }
+
+    /// Whether any of the bits below bit `n` is set.
+    const fn any_below(&self, n: usize) -> bool {
+        let mut i = 0;
+        while i < n {
+            if self.bit(i) {
+                return true;
+            }
+            i += 1;
+        }
+        false
+    }
+}
+
+/// `floor(n / d)` and whether the division left a remainder. The quotient must fit in a `u128`.
+const fn div(n: &Big, d: &Big) -> (u128, bool) {
+    let (n_len, d_len) = (n.bit_len(), d.bit_len());
+    if n_len < d_len {
+        return (0, !n.is_zero());
+    }
+    let steps = n_len - d_len + 1;
+    let mut rem = Big {
+        limbs: n.limbs,
+        len: n.len,
+    };
+    rem.shr(steps);
+    let mut q = 0u128;
+    let mut i = steps;
+    while i > 0 {
+        i -= 1;
+        rem.shl(1);
+        if n.bit(i) {
+            if rem.is_zero() {
+                rem.push(1);
+            } else {
+                rem.limbs[0] |= 1;
+            }
+        }
+        q <<= 1;
+        if rem.ge(d) {
+            rem.sub(d);
+            q |= 1;
+        }
+    }
+    (q, !rem.is_zero())
+}
+
+/// The bits of `sig * 2^exp` rounded to nearest, ties to even, where the lowest
+/// bit of `sig` may stand in for any non-zero bits below it. Out-of-range results do not compile.
+const fn round(exp: i64, sig: u128) -> u128 {
+    if sig == 0 {
+        return 0;
+    }
+    // The exponent of the last place the result keeps, for normal and then subnormal numbers.
+    let top = exp + 127 - sig.leading_zeros() as i64;
+    let mut unit = top - 112;
+    if unit < -16494 {
+        unit = -16494;
+    }
+    let shift = unit - exp;
+    let mut m = if shift <= 0 {
+        sig << -shift
+    } else if shift >= 128 {
+        0
+    } else {
+        let rem = sig & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        let m = sig >> shift;
+        if rem > half || rem == half && m & 1 != 0 {
+            m + 1
+        } else {
+            m
+        }
+    };
+    if m >> 113 != 0 {
+        m >>= 1;
+        unit += 1;
+    }
+    if m == 0 {
+        panic!("f128 literal is too small and rounds to zero");
+    }
+    if m >> 112 == 0 {
+        return m;
+    }
+    let biased = unit + 112 + 16383;
+    if biased >= 0x7fff {
+        panic!("f128 literal is too large and rounds to infinity");
+    }
+    (biased as u128) << 112 | m & ((1 << 112) - 1)
+}
+
+/// Reads an optionally signed exponent from `i` on, clamped to `EXP_LIMIT`.
+const fn exponent(bytes: &[u8], mut i: usize, end: usize) -> i64 {
+    let negative = i < end && bytes[i] == b'-';
+    if i < end && (bytes[i] == b'-' || bytes[i] == b'+') {
+        i += 1;
+    }
+    let mut any = false;
+    let mut exp = 0i64;
+    while i < end {
+        match bytes[i] {
+            b'0'..=b'9' => {
+                exp = exp * 10 + (bytes[i] - b'0') as i64;
+                if exp > EXP_LIMIT {
+                    exp = EXP_LIMIT;
+                }
+                any = true;
+            }
+            b'_' => {}
+            _ => panic!("invalid f128 literal"),
+        }
+        i += 1;
+    }
+    if !any {
+        panic!("invalid f128 literal");
+    }
+    if negative {
+        -exp
+    } else {
+        exp
+    }
+}
+
+const fn eq_ignore_case(bytes: &[u8], start: usize, end: usize, word: &[u8]) -> bool {
+    if end - start != word.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < word.len() {
+        if bytes[start + i].to_ascii_lowercase() != word[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The bits of a hexadecimal literal between `i` and `end`, after its `0x`.
+const fn hex(bytes: &[u8], mut i: usize, end: usize) -> u128 {
+    let mut sig = 0u128;
+    let mut sticky = false;
+    let mut exp = 0i64;
+    let mut any = false;
+    let mut point = false;
+    while i < end {
+        let c = bytes[i];
+        let digit = match c {
+            b'0'..=b'9' => c - b'0',
+            b'a'..=b'f' => c - b'a' + 10,
+            b'A'..=b'F' => c - b'A' + 10,
+            b'_' => {
+                i += 1;
+                continue;
+            }
+            b'.' if !point => {
+                point = true;
+                i += 1;
+                continue;
+            }
+            _ => break,
+        };
+        any = true;
+        // Keep the significand below 2^124 and fold the digits that do not fit into a sticky
+        // bit, which then lies well below the last place that can be kept.
+        if sig >> 120 == 0 {
+            sig = sig << 4 | digit as u128;
+            if point {
+                exp -= 4;
+            }
+        } else {
+            sticky |= digit != 0;
+            if !point {
+                exp += 4;
+            }
+        }
+        i += 1;
+    }
+    if !any {
+        panic!("invalid f128 literal");
+    }
+    if i < end {
+        if bytes[i] != b'p' && bytes[i] != b'P' {
+            panic!("invalid f128 literal");
+        }
+        exp += exponent(bytes, i + 1, end);
+    }
+    if sig == 0 {
+        return 0;
+    }
+    // Digits only drop once the significand has 121 bits, so the sticky bit lies below the half
+    // of the last place kept.
+    round(exp, sig | sticky as u128)
+}
+
+/// The bits of a decimal literal between `i` and `end`.
+const fn decimal(bytes: &[u8], mut i: usize, end: usize) -> u128 {
+    // The significant digits without trailing zeros, which are only multiplied in once another
+    // digit follows them.
+    let mut digits = Big::from_u32(0);
+    let mut count = 0usize;
+    let mut zeros = 0usize;
+    let mut frac_digits = 0i64;
+    let mut any = false;
+    let mut point = false;
+    while i < end {
+        let c = bytes[i];
+        match c {
+            b'0'..=b'9' => {
+                any = true;
+                frac_digits += point as i64;
+                if c == b'0' {
+                    zeros += !digits.is_zero() as usize;
+                } else {
+                    count += zeros + 1;
+                    if count > MAX_DIGITS {
+                        panic!("f128 literal has more than 1000 significant digits");
+                    }
+                    digits.mul_pow10(zeros as u64);
+                    digits.mul_add(10, (c - b'0') as u32);
+                    zeros = 0;
+                }
+            }
+            b'_' => {}
+            b'.' if !point => point = true,
+            _ => break,
+        }
+        i += 1;
+    }
+    if !any {
+        panic!("invalid f128 literal");
+    }
+    let mut exp = zeros as i64 - frac_digits;
+    if i < end {
+        if bytes[i] != b'e' && bytes[i] != b'E' {
+            panic!("invalid f128 literal");
+        }
+        exp += exponent(bytes, i + 1, end);
+    }
+    if digits.is_zero() {
+        return 0;
+    }
+
+    // The value lies in [10^(magnitude - 1), 10^magnitude). The largest finite value is about
+    // 1.19e4932 and half the smallest subnormal about 3.2e-4966.
+    let magnitude = count as i64 + exp;
+    if magnitude > 4933 {
+        panic!("f128 literal is too large and rounds to infinity");
+    }
+    if magnitude < -4965 {
+        panic!("f128 literal is too small and rounds to zero");
+    }
+
+    // As in `parse::reduce`: a significand of at most 127 bits with any discarded non-zero
+    // bits jammed into its lowest bit.
+    if exp >= 0 {
+        digits.mul_pow10(exp as u64);
+        let len = digits.bit_len();
+        if len <= 126 {
+            round(0, digits.bits_from(0))
+        } else {
+            let sticky = digits.any_below(len - 126) as u128;
+            round(len as i64 - 126, digits.bits_from(len - 126) | sticky)
+        }
+    } else {
+        let mut divisor = Big::from_u32(1);
+        divisor.mul_pow10(-exp as u64);
+        // Scale so that the quotient has 126 or 127 bits.
+        let shift = divisor.bit_len() as i64 - digits.bit_len() as i64 + 126;
+        if shift >= 0 {
+            digits.shl(shift as usize);
+        } else {
+            divisor.shl(-shift as usize);
+        }
+        let (quotient, inexact) = div(&digits, &divisor);
+        round(-shift, quotient | inexact as u128)
+    }
+}
+
+impl f128 {
+    /// The value of a literal as passed to `f128!`, negated if `negative`. Only meant to be
+    /// evaluated in a constant, where its panics are compile errors.
+    #[doc(hidden)]
+    pub const fn __from_literal(literal: &str, mut negative: bool) -> f128 {
+        let bytes = literal.as_bytes();
+        let (mut i, mut end) = (0, bytes.len());
+        // String literals arrive with their quotes, and may hold no escapes.
+        if end >= 2 && bytes[0] == b'"' && bytes[end - 1] == b'"' {
+            i += 1;
+            end -= 1;
+            let mut j = i;
+            while j < end {
+                if bytes[j] == b'\\' {
+                    panic!("invalid f128 literal");
+                }
+                j += 1;
+            }
+        }
+        if i < end && (bytes[i] == b'-' || bytes[i] == b'+') {
+            negative ^= bytes[i] == b'-';
+            i += 1;
+        }
+        let bits = if eq_ignore_case(bytes, i, end, b"inf")
+            || eq_ignore_case(bytes, i, end, b"infinity")
+        {
+            u128::from_ne_bytes(f128::INFINITY.0)
+        } else if eq_ignore_case(bytes, i, end, b"nan") {
+            u128::from_ne_bytes(f128::NAN.0)
+        } else if end - i > 2 && bytes[i] == b'0' && (bytes[i + 1] == b'x' || bytes[i + 1] == b'X')
+        {
+            hex(bytes, i + 2, end)
+        } else {
+            decimal(bytes, i, end)
+        };
+        f128(u128::to_ne_bytes(bits | (negative as u128) << 127))
+    }
+}
@@ -0,0 +1,109 @@
+//! The minimum and maximum operations of IEEE 754-2019.
+
+use f128_t::f128;
+use flags::{self, FpFlags};
+use num_traits::Float;
+use soft;
+use std::cmp::Ordering;
+
+impl f128 {
+    /// The larger of `self` and `other`, or NaN if either is NaN. `-0` counts as smaller than
+    /// `+0`. This is the `maximum` operation of IEEE 754-2019.
+    pub fn maximum(self, other: f128) -> f128 {
+        if self.is_nan() || other.is_nan() {
+            return soft::nan_result(self, other);
+        }
+        match self.total_cmp(&other) {
+            Ordering::Less => other,
+            _ => self,
+        }
+    }
+
+    /// The smaller of `self` and `other`, or NaN if either is NaN. `-0` counts as smaller than
+    /// `+0`. This is the `minimum` operation of IEEE 754-2019.
+    pub fn minimum(self, other: f128) -> f128 {
+        if self.is_nan() || other.is_nan() {
+            return soft::nan_result(self, other);
+        }
+        match self.total_cmp(&other) {
+            Ordering::Greater => other,
+            _ => self,
+        }
+    }
+
+    /// Like `maximum`, but a NaN only wins over another NaN: the larger number is returned if
+    /// there is one. This is the `maximumNumber` operation of IEEE 754-2019.
+    pub fn maximum_number(self, other: f128) -> f128 {
+        match number_or_nan(self, other) {
+            Some(x) => x,
+            None => self.maximum(other),
+        }
+    }
+
+    /// Like `minimum`, but a NaN only wins over another NaN: the smaller number is returned if
+    /// there is one. This is the `minimumNumber` operation of IEEE 754-2019.
+    pub fn minimum_number(self, other: f128) -> f128 {
+        match number_or_nan(self, other) {
+            Some(x) => x,
+            None => self.minimum(other),
+        }
+    }
+
+    /// Whichever of `self` and `other` has the larger magnitude, their `maximum` if the
+    /// magnitudes are equal, or NaN if either is NaN. This is the `maximumMagnitude` operation of
+    /// IEEE 754-2019.
+    pub fn max_magnitude(self, other: f128) -> f128 {
+        if self.is_nan() || other.is_nan() {
+            return soft::nan_result(self, other);
+        }
+        match self.abs().total_cmp(&other.abs()) {
+            Ordering::Greater => self,
+            Ordering::Less => other,
+            Ordering::Equal => self.maximum(other),
+        }
+    }
+
+    /// Whichever of `self` and `other` has the smaller magnitude, their `minimum` if the
+    /// magnitudes are equal, or NaN if either is NaN. This is the `minimumMagnitude` operation of
+    /// IEEE 754-2019.
+    pub fn min_magnitude(self, other: f128) -> f128 {
+        if self.is_nan() || other.is_nan() {
+            return soft::nan_result(self, other);
+        }
+        match self.abs().total_cmp(&other.abs()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => self.minimum(other),
+        }
+    }
+
+    /// `self` restricted to the interval `[min, max]`, or NaN if `self` is NaN. Zeros are ordered
+    /// as in `maximum` and `minimum`, so that clamping `-0` to `[+0, 1]` gives `+0`.
+    ///
+    /// # Panics
+    ///
+    /// If `min > max`, or if either of them is NaN.
+    pub fn clamp(self, min: f128, max: f128) -> f128 {
+        assert!(
+            !min.is_nan() && !max.is_nan() && min.total_cmp(&max) != Ordering::Greater,
+            "min > max, or either was NaN. min = {:?}, max = {:?}",
+            min,
+            max
+        );
+        self.maximum(min).minimum(max)
+    }
+}
+
+/// For the `*_number` operations: the operand that is not NaN if exactly one is, raising the
+/// invalid exception if the other is a signalling NaN, and `None` otherwise.
+fn number_or_nan(x: f128, y: f128) -> Option<f128> {
+    let (number, nan) = match (x.is_nan(), y.is_nan()) {
+        (false, true) => (x, y),
+        (true, false) => (y, x),
+        _ => return None,
+    };
+    if soft::is_signaling(nan) {
+        flags::raise(FpFlags::INVALID);
+    }
+    Some(number)
+}
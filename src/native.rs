@@ -0,0 +1,162 @@
+//! A backend built on the `f128` primitive of nightly compilers, enabled by the `native-f128`
+//! feature.
+//!
+//! It provides the same entry points as the C shims in `ffi`, so the `f128` newtype's operators,
+//! comparisons, conversions and `Float` methods compile down to the primitive's operations, which
+//! LLVM can inline, instead of out-of-line C calls. Conversions to integers follow Rust's `as`.
+
+use f128_t::f128;
+
+mod primitive {
+    // The crate's own `f128` is not imported here, so this names the built-in type.
+    pub type Native = f128;
+}
+
+/// The built-in `f128`, which the crate's `f128` shadows wherever it is imported.
+pub(crate) use self::primitive::Native;
+
+#[inline(always)]
+fn unwrap(x: f128) -> Native {
+    Native::from_bits(x.to_bits())
+}
+
+#[inline(always)]
+fn wrap(x: Native) -> f128 {
+    f128::from_bits(x.to_bits())
+}
+
+impl From<Native> for f128 {
+    #[inline]
+    fn from(x: Native) -> f128 {
+        wrap(x)
+    }
+}
+
+impl From<f128> for Native {
+    #[inline]
+    fn from(x: f128) -> Native {
+        unwrap(x)
+    }
+}
+
+macro_rules! binary_ops {
+    ($($name:ident => $op:tt),*) => ($(
+        #[inline]
+        pub fn $name(x: f128, y: f128) -> f128 {
+            wrap(unwrap(x) $op unwrap(y))
+        }
+    )*)
+}
+
+binary_ops! {
+    f128_add => +,
+    f128_sub => -,
+    f128_mul => *,
+    f128_div => /,
+    fmodq_f => %
+}
+
+macro_rules! comparisons {
+    ($($name:ident => $op:tt),*) => ($(
+        #[inline]
+        pub fn $name(lhs: f128, rhs: f128) -> u8 {
+            (unwrap(lhs) $op unwrap(rhs)) as u8
+        }
+    )*)
+}
+
+comparisons! {
+    gtq => >,
+    gteq => >=,
+    ltq => <,
+    lteq => <=,
+    eqq => ==,
+    neqq => !=
+}
+
+macro_rules! conversions {
+    ($($ty:ty, $to:ident, $from:ident);*) => ($(
+        #[inline]
+        pub fn $from(x: $ty) -> f128 {
+            wrap(x as Native)
+        }
+
+        #[inline]
+        pub fn $to(x: f128) -> $ty {
+            unwrap(x) as $ty
+        }
+    )*)
+}
+
+conversions! {
+    isize, f128_to_isize, isize_to_f128;
+    i8, f128_to_i8, i8_to_f128;
+    i16, f128_to_i16, i16_to_f128;
+    i32, f128_to_i32, i32_to_f128;
+    i64, f128_to_i64, i64_to_f128;
+    i128, f128_to_i128, i128_to_f128;
+    usize, f128_to_usize, usize_to_f128;
+    u8, f128_to_u8, u8_to_f128;
+    u16, f128_to_u16, u16_to_f128;
+    u32, f128_to_u32, u32_to_f128;
+    u64, f128_to_u64, u64_to_f128;
+    u128, f128_to_u128, u128_to_f128;
+    f32, f128_to_f32, f32_to_f128;
+    f64, f128_to_f64, f64_to_f128
+}
+
+macro_rules! unary_fns {
+    ($($name:ident => $method:ident),*) => ($(
+        #[inline]
+        pub fn $name(a: f128) -> f128 {
+            wrap(unwrap(a).$method())
+        }
+    )*)
+}
+
+unary_fns! {
+    acosq_f => acos,
+    acoshq_f => acosh,
+    asinq_f => asin,
+    asinhq_f => asinh,
+    atanq_f => atan,
+    atanhq_f => atanh,
+    cbrtq_f => cbrt,
+    ceilq_f => ceil,
+    coshq_f => cosh,
+    cosq_f => cos,
+    expq_f => exp,
+    expm1q_f => exp_m1,
+    floorq_f => floor,
+    logq_f => ln,
+    log10q_f => log10,
+    log2q_f => log2,
+    log1pq_f => ln_1p,
+    roundq_f => round,
+    sinhq_f => sinh,
+    sinq_f => sin,
+    sqrtq_f => sqrt,
+    tanq_f => tan,
+    tanhq_f => tanh,
+    truncq_f => trunc
+}
+
+macro_rules! binary_fns {
+    ($($name:ident => $method:ident),*) => ($(
+        #[inline]
+        pub fn $name(a: f128, b: f128) -> f128 {
+            wrap(unwrap(a).$method(unwrap(b)))
+        }
+    )*)
+}
+
+binary_fns! {
+    atan2q_f => atan2,
+    hypotq_f => hypot,
+    powq_f => powf
+}
+
+#[inline]
+pub fn fmaq_f(a: f128, b: f128, c: f128) -> f128 {
+    wrap(unwrap(a).mul_add(unwrap(b), unwrap(c)))
+}
@@ -0,0 +1,432 @@
+//! Correctly rounded parsing of `f128` from text.
+
+use bignum::Big;
+use f128_t::f128;
+use num_traits::{Float, Zero};
+use rounding::RoundingMode;
+use soft;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+
+/// The error returned when a string cannot be parsed as an `f128`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseF128Error {
+    kind: ParseF128ErrorKind,
+}
+
+/// What made a string unparseable.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseF128ErrorKind {
+    /// The string was empty, or held nothing but a sign.
+    Empty,
+    /// The string does not start with a number: the byte at `position` cannot appear there. A
+    /// `position` equal to the length of the string means it ended where more was expected, e.g.
+    /// after an exponent marker.
+    InvalidDigit { position: usize },
+    /// The string starts with a number, but more follows from byte `position` on.
+    TrailingCharacters { position: usize },
+    /// The number is finite but too large, and would round to infinity.
+    Overflow,
+    /// The number is not zero but too small, and would round to zero.
+    Underflow,
+}
+
+impl ParseF128Error {
+    pub(crate) fn new(kind: ParseF128ErrorKind) -> ParseF128Error {
+        ParseF128Error { kind }
+    }
+
+    pub fn kind(&self) -> &ParseF128ErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseF128Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseF128ErrorKind::Empty => f.write_str("cannot parse f128 from empty string"),
+            ParseF128ErrorKind::InvalidDigit { position } => {
+                write!(f, "invalid digit in f128 literal at position {}", position)
+            }
+            ParseF128ErrorKind::TrailingCharacters { position } => {
+                write!(
+                    f,
+                    "unexpected characters after f128 literal at position {}",
+                    position
+                )
+            }
+            ParseF128ErrorKind::Overflow => f.write_str("number too large to fit in f128"),
+            ParseF128ErrorKind::Underflow => f.write_str("number too small to fit in f128"),
+        }
+    }
+}
+
+impl Error for ParseF128Error {}
+
+fn error(kind: ParseF128ErrorKind) -> ParseF128Error {
+    ParseF128Error::new(kind)
+}
+
+/// Exponents are clamped to this magnitude while they are read; anything beyond it overflows or
+/// underflows regardless.
+const EXP_LIMIT: i64 = 1 << 40;
+
+/// Digits are accumulated until they have this many bits. A unit in the last of them is then
+/// less than 2^-127 of the value, too little to matter for rounding unless the value lies right
+/// next to a point where the result changes.
+const DIGIT_BITS: u64 = 128;
+
+/// A syntactically valid literal.
+enum Literal<'a> {
+    Special(f128),
+    /// `(-1)^negative * (digits + 0.tail) * radix^radix_exp * 2^bin_exp`, where `tail` holds the
+    /// digits past the first `DIGIT_BITS` bits, with the point if it came among them. It is empty
+    /// if they are all zero.
+    Number {
+        negative: bool,
+        digits: Big,
+        tail: &'a [u8],
+        radix: u32,
+        radix_exp: i64,
+        bin_exp: i64,
+    },
+}
+
+/// Reads a literal in `radix`: an optional sign followed by either `inf`, `infinity` or `nan` in
+/// any case, or digits with an optional point and an optional exponent. The exponent is written
+/// in decimal after an `e` (for radices up to 14, where `e` is not a digit), which scales by a
+/// power of the radix, or after a `p` (for radices up to 25), which scales by a power of two like
+/// C's hexadecimal floats do.
+///
+/// With `hex_prefix`, a `0x` or `0X` after the sign switches to radix 16.
+fn scan(s: &str, mut radix: u32, hex_prefix: bool) -> Result<Literal<'_>, ParseF128Error> {
+    let bytes = s.as_bytes();
+    let (negative, mut i) = match bytes.first() {
+        None => return Err(error(ParseF128ErrorKind::Empty)),
+        Some(&b'-') => (true, 1),
+        Some(&b'+') => (false, 1),
+        Some(_) => (false, 0),
+    };
+    let body = &s[i..];
+    if body.is_empty() {
+        return Err(error(ParseF128ErrorKind::Empty));
+    }
+    let special = if body.eq_ignore_ascii_case("inf") || body.eq_ignore_ascii_case("infinity") {
+        Some(f128::INFINITY)
+    } else if body.eq_ignore_ascii_case("nan") {
+        Some(f128::NAN)
+    } else {
+        None
+    };
+    if let Some(x) = special {
+        return Ok(Literal::Special(if negative { -x } else { x }));
+    }
+    if hex_prefix && (body.starts_with("0x") || body.starts_with("0X")) {
+        radix = 16;
+        i += 2;
+    }
+
+    let mut digits = Big::zero();
+    let mut any_digit = false;
+    let mut point = false;
+    let mut frac_digits = 0i64;
+    // Accumulating every digit would take time quadratic in their number, so the ones past
+    // `DIGIT_BITS` bits are only checked for being zero here.
+    let mut tail_start = None;
+    let mut tail_zero = true;
+    let mut int_digits_dropped = 0i64;
+    while i < bytes.len() {
+        match (bytes[i] as char).to_digit(radix) {
+            Some(d) if digits.bit_len() < DIGIT_BITS => {
+                digits.mul_add_small(radix, d);
+                any_digit = true;
+                frac_digits += point as i64;
+            }
+            Some(d) => {
+                tail_start = tail_start.or(Some(i));
+                tail_zero &= d == 0;
+                int_digits_dropped += !point as i64;
+            }
+            None if bytes[i] == b'.' && !point => point = true,
+            None => break,
+        }
+        i += 1;
+    }
+    if !any_digit {
+        return Err(error(ParseF128ErrorKind::InvalidDigit { position: i }));
+    }
+    let tail = match tail_start {
+        Some(start) if !tail_zero => &bytes[start..i],
+        _ => &[],
+    };
+
+    let (mut radix_exp, mut bin_exp) = (0, 0);
+    let scales_by_radix = match bytes.get(i) {
+        Some(&b'e') | Some(&b'E') if radix < 15 => Some(true),
+        Some(&b'p') | Some(&b'P') if radix < 26 => Some(false),
+        _ => None,
+    };
+    if let Some(scales_by_radix) = scales_by_radix {
+        i += 1;
+        let exp_negative = bytes.get(i) == Some(&b'-');
+        if exp_negative || bytes.get(i) == Some(&b'+') {
+            i += 1;
+        }
+        let exp_start = i;
+        let mut exp = 0i64;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            exp = (exp * 10 + (bytes[i] - b'0') as i64).min(EXP_LIMIT);
+            i += 1;
+        }
+        if i == exp_start {
+            return Err(error(ParseF128ErrorKind::InvalidDigit { position: i }));
+        }
+        let exp = if exp_negative { -exp } else { exp };
+        if scales_by_radix {
+            radix_exp = exp;
+        } else {
+            bin_exp = exp;
+        }
+    }
+    if i < bytes.len() {
+        return Err(error(ParseF128ErrorKind::TrailingCharacters {
+            position: i,
+        }));
+    }
+
+    Ok(Literal::Number {
+        negative,
+        digits,
+        tail,
+        radix,
+        radix_exp: radix_exp - frac_digits + int_digits_dropped,
+        bin_exp,
+    })
+}
+
+/// Rounds a literal correctly in the current rounding mode.
+fn round(literal: Literal) -> f128 {
+    match literal {
+        Literal::Special(x) => x,
+        Literal::Number {
+            negative,
+            digits,
+            tail,
+            radix,
+            radix_exp,
+            bin_exp,
+        } => {
+            let mode = RoundingMode::current();
+            if tail.is_empty() {
+                let (exp, significand) = reduce(digits, radix, radix_exp, bin_exp);
+                soft::from_parts(negative, exp, significand, mode)
+            } else {
+                round_with_tail(negative, &digits, tail, radix, radix_exp, bin_exp, mode)
+            }
+        }
+    }
+}
+
+/// Parses `s` as a number in `radix` as described for `scan`. Numbers out of range become
+/// infinity or zero.
+///
+/// # Panics
+///
+/// If `radix` is not in the range `2..=36`.
+pub(crate) fn from_str_radix(s: &str, radix: u32) -> Result<f128, ParseF128Error> {
+    assert!(
+        radix >= 2 && radix <= 36,
+        "from_str_radix: radix must lie in the range `[2, 36]` - found {}",
+        radix
+    );
+    scan(s, radix, false).map(round)
+}
+
+/// Parses a hexadecimal literal with an optional `0x` prefix.
+pub(crate) fn from_hex_str(s: &str) -> Result<f128, ParseF128Error> {
+    scan(s, 16, true).map(round)
+}
+
+/// Parses a decimal literal, or a hexadecimal one with a `0x` prefix, as `strtoflt128` does:
+/// numbers out of range become infinity or zero.
+pub(crate) fn from_c_str(s: &str) -> Result<f128, ParseF128Error> {
+    scan(s, 10, true).map(round)
+}
+
+/// Parses a decimal literal, or a hexadecimal one with a `0x` prefix. Unlike `from_str_radix`,
+/// numbers that round to infinity or zero are errors.
+pub(crate) fn from_str(s: &str) -> Result<f128, ParseF128Error> {
+    let literal = scan(s, 10, true)?;
+    let nonzero = match literal {
+        Literal::Special(_) => return Ok(round(literal)),
+        Literal::Number { ref digits, .. } => !digits.is_zero(),
+    };
+    let x = round(literal);
+    if x.is_infinite() {
+        Err(error(ParseF128ErrorKind::Overflow))
+    } else if nonzero && x.is_zero() {
+        Err(error(ParseF128ErrorKind::Underflow))
+    } else {
+        Ok(x)
+    }
+}
+
+/// `digits * radix^radix_exp * 2^bin_exp` as an exponent and a significand of at most 127 bits
+/// for `soft::from_parts`, with any discarded non-zero bits jammed into its lowest bit so that
+/// rounding still sees them.
+fn reduce(mut digits: Big, radix: u32, mut radix_exp: i64, mut bin_exp: i64) -> (i32, u128) {
+    if digits.is_zero() {
+        return (0, 0);
+    }
+    if radix.is_power_of_two() {
+        bin_exp += radix_exp * radix.trailing_zeros() as i64;
+        radix_exp = 0;
+    }
+
+    // Settle numbers far outside the exponent range without computing huge powers. The binary
+    // logarithm of the value is within one of this estimate.
+    let log2 = digits.bit_len() as f64 + radix_exp as f64 * (radix as f64).log2() + bin_exp as f64;
+    if log2 > 16_400.0 {
+        return (40_000, 1);
+    }
+    if log2 < -16_520.0 {
+        return (-40_000, 1);
+    }
+
+    let (significand, exp) = if radix_exp >= 0 {
+        digits.mul_pow(radix, radix_exp as u64);
+        let len = digits.bit_len();
+        if len <= 126 {
+            (digits.bits_from(0), 0)
+        } else {
+            let sticky = digits.any_below(len - 126) as u128;
+            (digits.bits_from(len - 126) | sticky, len as i64 - 126)
+        }
+    } else {
+        let mut divisor = Big::from_u128(1);
+        divisor.mul_pow(radix, -radix_exp as u64);
+        // Scale so that the quotient has 126 or 127 bits.
+        let shift = divisor.bit_len() as i64 - digits.bit_len() as i64 + 126;
+        if shift >= 0 {
+            digits.shl(shift as u64);
+        } else {
+            divisor.shl(-shift as u64);
+        }
+        let (quotient, inexact) = digits.div_rem_small_quotient(&divisor);
+        (quotient | inexact as u128, -shift)
+    };
+    ((exp + bin_exp).max(-40_000).min(40_000) as i32, significand)
+}
+
+/// Rounds `(-1)^negative * (digits + 0.tail) * radix^radix_exp * 2^bin_exp` in `mode`, for a
+/// `tail` that is not all zeros and `digits` of at least `DIGIT_BITS` bits.
+///
+/// The value lies strictly between `digits` and `digits + 1` units, and that interval is narrow
+/// enough to hold at most one point where rounding changes: a representable value, which decides
+/// exactness, tininess and the directed modes, or a halfway point between two of them. Only if
+/// there is one does the result depend on `tail`, which is then compared with it digit by digit.
+fn round_with_tail(
+    negative: bool,
+    digits: &Big,
+    tail: &[u8],
+    radix: u32,
+    radix_exp: i64,
+    bin_exp: i64,
+    mode: RoundingMode,
+) -> f128 {
+    // The exponent and jammed significand of a value just above `digits + units`.
+    let above = |units: u32| {
+        let mut bound = digits.clone();
+        bound.mul_add_small(1, units);
+        let (exp, significand) = reduce(bound, radix, radix_exp, bin_exp);
+        (exp, significand | 1)
+    };
+    let probe = |(exp, significand): (i32, u128), mode| {
+        let (x, raised) = soft::from_parts_quietly(negative, exp, significand, mode);
+        (x.to_bits(), raised)
+    };
+    let (low, high) = (above(0), above(1));
+    let (low_zero, high_zero) = (
+        probe(low, RoundingMode::TowardZero).0,
+        probe(high, RoundingMode::TowardZero).0,
+    );
+    let point = if low_zero != high_zero {
+        let (_, exp, significand) = f128::from_bits(high_zero).decode();
+        (exp, significand)
+    } else if probe(low, mode) != probe(high, mode) {
+        // Only rounding to nearest changes between representable values.
+        let (_, low_exp, low_sig) = f128::from_bits(probe(low, mode).0).decode();
+        let (_, high_exp, high_sig) = f128::from_bits(probe(high, mode).0).decode();
+        let exp = low_exp.min(high_exp);
+        (exp - 1, (low_sig << (low_exp - exp)) + (high_sig << (high_exp - exp)))
+    } else {
+        return soft::from_parts(negative, low.0, low.1, mode);
+    };
+    let (exp, significand) = match cmp_tail(digits, tail, radix, radix_exp, bin_exp, point) {
+        Ordering::Less => low,
+        Ordering::Equal => point,
+        Ordering::Greater => (point.0 - 12, point.1 << 12 | 1),
+    };
+    soft::from_parts(negative, exp, significand, mode)
+}
+
+/// How `(digits + 0.tail) * radix^radix_exp * 2^bin_exp` compares with `significand * 2^exp`,
+/// in time linear in the length of `tail`.
+fn cmp_tail(
+    digits: &Big,
+    tail: &[u8],
+    radix: u32,
+    radix_exp: i64,
+    bin_exp: i64,
+    (exp, significand): (i32, u128),
+) -> Ordering {
+    // Divided by `radix^radix_exp * 2^bin_exp`, the other side is `digits + x / y`, and the
+    // tail is compared with the fraction `x / y` one digit at a time.
+    let bin_exp = exp as i64 - bin_exp;
+    let (mut x, mut y) = (Big::from_u128(significand), Big::from_u128(1));
+    let mut scaled = digits.clone();
+    if bin_exp >= 0 {
+        x.shl(bin_exp as u64);
+    } else {
+        y.shl(-bin_exp as u64);
+        scaled.shl(-bin_exp as u64);
+    }
+    if radix_exp >= 0 {
+        y.mul_pow(radix, radix_exp as u64);
+        scaled.mul_pow(radix, radix_exp as u64);
+    } else {
+        x.mul_pow(radix, -radix_exp as u64);
+    }
+    if x < scaled {
+        return Ordering::Greater;
+    }
+    x.sub_assign(&scaled);
+
+    let multiples: Vec<Big> = (0..radix)
+        .map(|d| {
+            let mut m = y.clone();
+            m.mul_add_small(d, 0);
+            m
+        })
+        .collect();
+    for &b in tail {
+        if x >= y {
+            return Ordering::Less;
+        }
+        // Skip the point.
+        if let Some(d) = (b as char).to_digit(radix) {
+            x.mul_add_small(radix, 0);
+            if x < multiples[d as usize] {
+                return Ordering::Greater;
+            }
+            x.sub_assign(&multiples[d as usize]);
+        }
+    }
+    if x.is_zero() {
+        Ordering::Equal
+    } else {
+        Ordering::Less
+    }
+}
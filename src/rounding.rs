@@ -0,0 +1,123 @@
+use f128_t::f128;
+use ffi;
+use flags;
+use soft;
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+/// The IEEE 754 rounding-direction attributes `f128` arithmetic can use.
+///
+/// The mode is per thread and defaults to `NearestEven`. It applies to `+`, `-`, `*`, `/`,
+/// `sqrt` and to the conversions that can round: from `u128`/`i128` and to `f32`/`f64`.
+/// Conversions to integers always truncate, like `as`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round to the nearest representable value, ties to the one with an even significand.
+    NearestEven,
+    /// Round toward zero, i.e. truncate.
+    TowardZero,
+    /// Round toward positive infinity.
+    Up,
+    /// Round toward negative infinity.
+    Down,
+}
+
+impl Default for RoundingMode {
+    #[inline]
+    fn default() -> RoundingMode {
+        RoundingMode::NearestEven
+    }
+}
+
+thread_local! {
+    static MODE: Cell<RoundingMode> = const { Cell::new(RoundingMode::NearestEven) };
+}
+
+impl RoundingMode {
+    /// The rounding mode in effect on the current thread.
+    #[inline]
+    pub fn current() -> RoundingMode {
+        MODE.with(|m| m.get())
+    }
+}
+
+/// The rounding mode to run the pure-Rust implementation in `soft` with, or `None` if the backend
+/// can do the operation itself. Every backend rounds to nearest-even, but only `soft` supports the
+/// directed modes and reports exception flags.
+#[inline]
+pub(crate) fn soft_mode() -> Option<RoundingMode> {
+    match RoundingMode::current() {
+        RoundingMode::NearestEven if !flags::recording() => None,
+        mode => Some(mode),
+    }
+}
+
+/// Sets the rounding mode of the current thread until it is dropped, then restores the previous
+/// one.
+///
+/// Guards must be dropped in the reverse order they were created in; `with_rounding` takes care
+/// of that.
+#[must_use = "the rounding mode is restored as soon as the guard is dropped"]
+pub struct RoundingGuard {
+    previous: RoundingMode,
+    // The mode is thread-local, so the guard must not move to another thread.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl RoundingGuard {
+    pub fn new(mode: RoundingMode) -> RoundingGuard {
+        let previous = MODE.with(|m| m.replace(mode));
+        RoundingGuard {
+            previous,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+impl Drop for RoundingGuard {
+    fn drop(&mut self) {
+        MODE.with(|m| m.set(self.previous));
+    }
+}
+
+/// Runs `f` with `mode` as the current thread's rounding mode, restoring the previous mode
+/// afterwards, even if `f` panics.
+pub fn with_rounding<T, F: FnOnce() -> T>(mode: RoundingMode, f: F) -> T {
+    let _guard = RoundingGuard::new(mode);
+    f()
+}
+
+// The conversions that can round, dispatching to `soft` for the directed modes like the
+// arithmetic operators do.
+
+#[inline]
+pub(crate) fn u128_to_f128(x: u128) -> f128 {
+    match soft_mode() {
+        None => unsafe { ffi::u128_to_f128(x) },
+        Some(mode) => soft::from_u128(x, mode),
+    }
+}
+
+#[inline]
+pub(crate) fn i128_to_f128(x: i128) -> f128 {
+    match soft_mode() {
+        None => unsafe { ffi::i128_to_f128(x) },
+        Some(mode) => soft::from_i128(x, mode),
+    }
+}
+
+#[inline]
+pub(crate) fn f128_to_f32(x: f128) -> f32 {
+    match soft_mode() {
+        None => unsafe { ffi::f128_to_f32(x) },
+        Some(mode) => soft::to_f32(x, mode),
+    }
+}
+
+#[inline]
+pub(crate) fn f128_to_f64(x: f128) -> f64 {
+    match soft_mode() {
+        None => unsafe { ffi::f128_to_f64(x) },
+        Some(mode) => soft::to_f64(x, mode),
+    }
+}
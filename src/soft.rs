@@ -0,0 +1,750 @@
+//! A pure-Rust implementation of the IEEE 754 binary128 operations that `src/f128.c` otherwise
+//! provides: `+`, `-`, `*`, `/`, the comparison shims and the conversions to and from the
+//! primitive types. The math library functions are in [`math`].
+//!
+//! It is compiled in place of the C shims when the `soft-float` feature is enabled, which takes
+//! `src/f128.c` and libquadmath out of the build altogether. Results are correctly rounded
+//! and bit-for-bit identical to what the C shims return, NaN payloads included. Conversions of
+//! NaN, infinities and out-of-range values to integers are undefined behaviour in C; here they
+//! follow Rust's `as`: NaN becomes zero and everything else saturates.
+//!
+//! Every backend also uses this module for the directed rounding modes and for recording exception
+//! flags, neither of which the C shims or the `f128` primitive support.
+
+use f128_t::f128;
+use flags::{self, FpFlags};
+use rounding::RoundingMode;
+use std::cmp::Ordering;
+
+pub mod math;
+
+const SIGN_MASK: u128 = 1 << 127;
+const EXP_MASK: u128 = 0x7fff << 112;
+const FRAC_MASK: u128 = (1 << 112) - 1;
+const QUIET_BIT: u128 = 1 << 111;
+const BIAS: i32 = 16383;
+
+/// The NaN produced by invalid operations such as `0 / 0`.
+const DEFAULT_NAN: u128 = EXP_MASK | QUIET_BIT;
+
+/// The layout of an IEEE 754 binary interchange format.
+#[derive(Clone, Copy)]
+struct Format {
+    frac_bits: u32,
+    exp_bits: u32,
+}
+
+impl Format {
+    #[inline]
+    fn exp_max(self) -> i32 {
+        (1 << self.exp_bits) - 1
+    }
+
+    #[inline]
+    fn bias(self) -> i32 {
+        self.exp_max() >> 1
+    }
+
+    #[inline]
+    fn sign_bit(self, sign: bool) -> u128 {
+        (sign as u128) << (self.frac_bits + self.exp_bits)
+    }
+}
+
+const BINARY128: Format = Format {
+    frac_bits: 112,
+    exp_bits: 15,
+};
+const BINARY64: Format = Format {
+    frac_bits: 52,
+    exp_bits: 11,
+};
+const BINARY32: Format = Format {
+    frac_bits: 23,
+    exp_bits: 8,
+};
+
+#[inline]
+fn is_nan(b: u128) -> bool {
+    b & !SIGN_MASK > EXP_MASK
+}
+
+#[inline]
+fn is_snan(b: u128) -> bool {
+    is_nan(b) && b & QUIET_BIT == 0
+}
+
+#[inline]
+fn is_inf(b: u128) -> bool {
+    b & !SIGN_MASK == EXP_MASK
+}
+
+#[inline]
+fn is_zero(b: u128) -> bool {
+    b & !SIGN_MASK == 0
+}
+
+#[inline]
+fn sign_of(b: u128) -> bool {
+    b & SIGN_MASK != 0
+}
+
+/// Splits a finite, non-zero binary128 into its biased exponent and its significand, with the
+/// significand normalised so that the integer bit is bit 112. Subnormals get an exponent below 1.
+#[inline]
+fn unpack(b: u128) -> (i32, u128) {
+    let exp = ((b & EXP_MASK) >> 112) as i32;
+    let frac = b & FRAC_MASK;
+    if exp == 0 {
+        let shift = frac.leading_zeros() - 15;
+        (1 - shift as i32, frac << shift)
+    } else {
+        (exp, frac | 1 << 112)
+    }
+}
+
+/// Shifts `x` right by `n` bits, OR-ing any bits shifted out into the lowest bit so that the
+/// result still remembers whether it was inexact.
+#[inline]
+fn shift_right_jam(x: u128, n: u32) -> u128 {
+    if n == 0 {
+        x
+    } else if n < 128 {
+        (x >> n) | ((x << (128 - n) != 0) as u128)
+    } else {
+        (x != 0) as u128
+    }
+}
+
+/// Multiplies two `u128`s into a 256-bit `(high, low)` pair.
+#[inline]
+pub fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const LO: u128 = (1 << 64) - 1;
+    let (a1, a0) = (a >> 64, a & LO);
+    let (b1, b0) = (b >> 64, b & LO);
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+    let mid = (p00 >> 64) + (p01 & LO) + (p10 & LO);
+    let lo = (p00 & LO) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+/// Whether rounding in `mode` moves a value with the given sign away from zero when it is not
+/// exactly halfway between two representable values.
+#[inline]
+fn rounds_away(mode: RoundingMode, sign: bool) -> bool {
+    match mode {
+        RoundingMode::NearestEven => true,
+        RoundingMode::TowardZero => false,
+        RoundingMode::Up => !sign,
+        RoundingMode::Down => sign,
+    }
+}
+
+/// The sign of an exact zero sum of operands with opposite signs: positive, except when rounding
+/// down.
+#[inline]
+fn zero_sum(mode: RoundingMode) -> u128 {
+    if mode == RoundingMode::Down {
+        SIGN_MASK
+    } else {
+        0
+    }
+}
+
+/// Rounds `(-1)^sign * sig * 2^(exp - bias - frac_bits - 3)` according to `mode` and packs it
+/// into `fmt`, along with the exceptions that raises.
+///
+/// `sig` carries three extra rounding bits below the fraction and must be normalised: its leading
+/// one is at bit `frac_bits + 3`. `exp` may fall below 1, in which case the result is subnormal.
+/// Tininess is detected before rounding.
+fn round_pack(
+    fmt: Format,
+    mode: RoundingMode,
+    sign: bool,
+    mut exp: i32,
+    mut sig: u128,
+) -> (u128, FpFlags) {
+    let sign_bit = fmt.sign_bit(sign);
+    let tiny = exp <= 0;
+    if tiny {
+        sig = shift_right_jam(sig, (1 - exp) as u32);
+        exp = 0;
+    }
+
+    let rest = sig & 7;
+    let flags = match (rest, tiny) {
+        (0, _) => FpFlags::empty(),
+        (_, true) => FpFlags::UNDERFLOW | FpFlags::INEXACT,
+        (_, false) => FpFlags::INEXACT,
+    };
+    sig >>= 3;
+    let increment = match mode {
+        RoundingMode::NearestEven => rest > 4 || (rest == 4 && sig & 1 != 0),
+        _ => rest != 0 && rounds_away(mode, sign),
+    };
+    if increment {
+        sig += 1;
+    }
+
+    if sig >> (fmt.frac_bits + 1) != 0 {
+        // Rounding carried out of the significand; the low bit is zero so this is exact.
+        sig >>= 1;
+        exp += 1;
+    } else if exp == 0 && sig >> fmt.frac_bits != 0 {
+        // A subnormal rounded up to the smallest normal.
+        exp = 1;
+    }
+
+    if exp >= fmt.exp_max() {
+        let inf = sign_bit | (fmt.exp_max() as u128) << fmt.frac_bits;
+        let max = if rounds_away(mode, sign) { inf } else { inf - 1 };
+        return (max, FpFlags::OVERFLOW | FpFlags::INEXACT);
+    }
+    let packed = sign_bit | (exp as u128) << fmt.frac_bits | sig & ((1 << fmt.frac_bits) - 1);
+    (packed, flags)
+}
+
+#[inline]
+fn exact(b: u128) -> (u128, FpFlags) {
+    (b, FpFlags::empty())
+}
+
+#[inline]
+fn invalid() -> (u128, FpFlags) {
+    (DEFAULT_NAN, FpFlags::INVALID)
+}
+
+/// Picks the NaN an operation on `a` and `b` returns, at least one of which is a NaN: the first
+/// NaN operand, quieted. Addition and subtraction also clear its sign. Signalling NaNs raise
+/// the invalid exception.
+fn propagate_nan(a: u128, b: u128, clear_sign: bool) -> (u128, FpFlags) {
+    let nan = if is_nan(a) { a } else { b };
+    let flags = if is_snan(a) || is_snan(b) {
+        FpFlags::INVALID
+    } else {
+        FpFlags::empty()
+    };
+    if clear_sign {
+        (nan & !SIGN_MASK | QUIET_BIT, flags)
+    } else {
+        (nan | QUIET_BIT, flags)
+    }
+}
+
+fn add_bits(a: u128, b: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    let (sa, sb) = (sign_of(a), sign_of(b));
+    if is_inf(a) {
+        return if is_inf(b) && sa != sb {
+            invalid()
+        } else {
+            exact(a)
+        };
+    }
+    if is_inf(b) {
+        return exact(b);
+    }
+    if is_zero(a) {
+        return exact(if !is_zero(b) {
+            b
+        } else if sa == sb {
+            a
+        } else {
+            zero_sum(mode)
+        });
+    }
+    if is_zero(b) {
+        return exact(a);
+    }
+
+    let (mut ea, mut ma) = unpack(a);
+    let (mut eb, mut mb) = unpack(b);
+    let mut sign = sa;
+    if (ea, ma) < (eb, mb) {
+        ::std::mem::swap(&mut ea, &mut eb);
+        ::std::mem::swap(&mut ma, &mut mb);
+        sign = sb;
+    }
+    let ma = ma << 3;
+    let mb = shift_right_jam(mb << 3, (ea - eb) as u32);
+
+    let mut exp = ea;
+    let mut sig;
+    if sa == sb {
+        sig = ma + mb;
+        if sig >> 116 != 0 {
+            sig = shift_right_jam(sig, 1);
+            exp += 1;
+        }
+    } else {
+        sig = ma - mb;
+        if sig == 0 {
+            return exact(zero_sum(mode));
+        }
+        let shift = sig.leading_zeros() - 12;
+        sig <<= shift;
+        exp -= shift as i32;
+    }
+    round_pack(BINARY128, mode, sign, exp, sig)
+}
+
+fn mul_bits(a: u128, b: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    let sign = sign_of(a) != sign_of(b);
+    let sign_bit = BINARY128.sign_bit(sign);
+    if is_inf(a) || is_inf(b) {
+        return if is_zero(a) || is_zero(b) {
+            invalid()
+        } else {
+            exact(sign_bit | EXP_MASK)
+        };
+    }
+    if is_zero(a) || is_zero(b) {
+        return exact(sign_bit);
+    }
+
+    let (ea, ma) = unpack(a);
+    let (eb, mb) = unpack(b);
+    let (hi, lo) = mul_wide(ma, mb);
+    // The product has its leading one at bit 224 or 225; bring it down to bit 115 or 116.
+    let mut sig = (hi << 19) | (lo >> 109) | ((lo & ((1 << 109) - 1) != 0) as u128);
+    let mut exp = ea + eb - BIAS;
+    if sig >> 116 != 0 {
+        sig = shift_right_jam(sig, 1);
+        exp += 1;
+    }
+    round_pack(BINARY128, mode, sign, exp, sig)
+}
+
+fn div_bits(a: u128, b: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    let sign = sign_of(a) != sign_of(b);
+    let sign_bit = BINARY128.sign_bit(sign);
+    if is_inf(a) {
+        return if is_inf(b) {
+            invalid()
+        } else {
+            exact(sign_bit | EXP_MASK)
+        };
+    }
+    if is_inf(b) {
+        return exact(sign_bit);
+    }
+    if is_zero(b) {
+        return if is_zero(a) {
+            invalid()
+        } else {
+            (sign_bit | EXP_MASK, FpFlags::DIV_BY_ZERO)
+        };
+    }
+    if is_zero(a) {
+        return exact(sign_bit);
+    }
+
+    let (ea, mut ma) = unpack(a);
+    let (eb, mb) = unpack(b);
+    let mut exp = ea - eb + BIAS;
+    if ma < mb {
+        ma <<= 1;
+        exp -= 1;
+    }
+
+    // Restoring long division, producing the 116 bits the rounding step needs.
+    let mut rem = ma;
+    let mut sig = 0u128;
+    for _ in 0..116 {
+        sig <<= 1;
+        if rem >= mb {
+            rem -= mb;
+            sig |= 1;
+        }
+        rem <<= 1;
+    }
+    round_pack(BINARY128, mode, sign, exp, sig | (rem != 0) as u128)
+}
+
+fn sqrt_bits(a: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    if is_zero(a) || a == EXP_MASK {
+        return exact(a);
+    }
+    if sign_of(a) {
+        return invalid();
+    }
+
+    let (exp, mut sig) = unpack(a);
+    let mut exp = exp - BIAS;
+    if exp & 1 != 0 {
+        sig <<= 1;
+        exp -= 1;
+    }
+
+    // The integer square root of sig * 2^118 has exactly 116 bits. Compute it two radicand bits
+    // at a time; the radicand is at most 232 bits wide, held in (hi, lo).
+    let (hi, lo) = (sig >> 10, sig << 118);
+    let mut rem = 0u128;
+    let mut root = 0u128;
+    for i in (0..116).rev() {
+        let pos = 2 * i;
+        let pair = if pos >= 128 { hi >> (pos - 128) } else { lo >> pos } & 3;
+        rem = (rem << 2) | pair;
+        let trial = (root << 2) | 1;
+        root <<= 1;
+        if rem >= trial {
+            rem -= trial;
+            root |= 1;
+        }
+    }
+    round_pack(BINARY128, mode, false, exp / 2 + BIAS, root | (rem != 0) as u128)
+}
+
+fn cmp_bits(a: u128, b: u128) -> Option<Ordering> {
+    if is_nan(a) || is_nan(b) {
+        return None;
+    }
+    // Map sign-magnitude onto two's complement; both zeros map to 0.
+    let key = |x: u128| {
+        let mag = (x & !SIGN_MASK) as i128;
+        if sign_of(x) {
+            -mag
+        } else {
+            mag
+        }
+    };
+    Some(key(a).cmp(&key(b)))
+}
+
+/// Rounds `(-1)^sign * mag * 2^exp` to binary128. A zero `mag` gives a zero with that sign.
+fn from_scaled_int(sign: bool, mag: u128, exp: i32, mode: RoundingMode) -> (u128, FpFlags) {
+    if mag == 0 {
+        return exact(BINARY128.sign_bit(sign));
+    }
+    // Anything outside this range overflows or underflows all the same, and clamping keeps the
+    // exponent arithmetic from overflowing.
+    let exp = exp.max(-40_000).min(40_000);
+    let lz = mag.leading_zeros();
+    let sig = if lz >= 12 {
+        mag << (lz - 12)
+    } else {
+        shift_right_jam(mag, 12 - lz)
+    };
+    round_pack(BINARY128, mode, sign, BIAS + 127 - lz as i32 + exp, sig)
+}
+
+/// Rounds the integer `(-1)^sign * mag` to binary128.
+#[inline]
+fn from_int(sign: bool, mag: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    if mag == 0 {
+        return exact(0);
+    }
+    from_scaled_int(sign, mag, 0, mode)
+}
+
+/// The magnitude of `b` truncated toward zero, or `None` if it is NaN, infinite or does not fit
+/// in a `u128`.
+fn trunc_magnitude(b: u128) -> Option<u128> {
+    let exp = ((b & EXP_MASK) >> 112) as i32;
+    if exp == 0x7fff {
+        return None;
+    }
+    if exp < BIAS {
+        return Some(0);
+    }
+    let sig = (b & FRAC_MASK) | 1 << 112;
+    match exp - BIAS {
+        e if e >= 128 => None,
+        e if e >= 112 => Some(sig << (e - 112)),
+        e => Some(sig >> (112 - e)),
+    }
+}
+
+fn to_signed(b: u128, min: i128, max: i128) -> i128 {
+    if is_nan(b) {
+        return 0;
+    }
+    let neg = sign_of(b);
+    match trunc_magnitude(b) {
+        None if neg => min,
+        None => max,
+        Some(m) if neg => {
+            if m > min.unsigned_abs() {
+                min
+            } else {
+                0i128.wrapping_sub(m as i128)
+            }
+        }
+        Some(m) => {
+            if m > max as u128 {
+                max
+            } else {
+                m as i128
+            }
+        }
+    }
+}
+
+fn to_unsigned(b: u128, max: u128) -> u128 {
+    if is_nan(b) || sign_of(b) {
+        return 0;
+    }
+    match trunc_magnitude(b) {
+        Some(m) if m <= max => m,
+        _ => max,
+    }
+}
+
+/// Widens a binary32 or binary64 bit pattern to binary128, which is always exact. NaN payloads
+/// are kept as they are, signalling NaNs included.
+fn from_narrow(fmt: Format, bits: u128) -> u128 {
+    let sign = BINARY128.sign_bit(bits >> (fmt.frac_bits + fmt.exp_bits) & 1 != 0);
+    let exp = (bits >> fmt.frac_bits) as i32 & fmt.exp_max();
+    let frac = bits & ((1 << fmt.frac_bits) - 1);
+    let shift = 112 - fmt.frac_bits;
+    if exp == fmt.exp_max() {
+        return if frac == 0 {
+            sign | EXP_MASK
+        } else {
+            sign | EXP_MASK | frac << shift
+        };
+    }
+    if exp == 0 {
+        if frac == 0 {
+            return sign;
+        }
+        let top = 127 - frac.leading_zeros() as i32;
+        let exp = top + 1 - fmt.bias() - fmt.frac_bits as i32 + BIAS;
+        return sign | (exp as u128) << 112 | (frac << (112 - top)) & FRAC_MASK;
+    }
+    let exp = exp - fmt.bias() + BIAS;
+    sign | (exp as u128) << 112 | frac << shift
+}
+
+/// Rounds a binary128 bit pattern to binary32 or binary64.
+fn to_narrow(fmt: Format, b: u128, mode: RoundingMode) -> (u128, FpFlags) {
+    let sign = sign_of(b);
+    let sign_bit = fmt.sign_bit(sign);
+    let inf = sign_bit | (fmt.exp_max() as u128) << fmt.frac_bits;
+    if is_nan(b) {
+        let quiet = 1 << (fmt.frac_bits - 1);
+        let nan = inf | quiet | (b & FRAC_MASK) >> (112 - fmt.frac_bits);
+        let flags = if is_snan(b) {
+            FpFlags::INVALID
+        } else {
+            FpFlags::empty()
+        };
+        return (nan, flags);
+    }
+    if is_inf(b) {
+        return exact(inf);
+    }
+    if is_zero(b) {
+        return exact(sign_bit);
+    }
+    let (exp, sig) = unpack(b);
+    let sig = shift_right_jam(sig, 112 - fmt.frac_bits - 3);
+    round_pack(fmt, mode, sign, exp - BIAS + fmt.bias(), sig)
+}
+
+#[inline]
+fn bits(x: f128) -> u128 {
+    x.to_bits()
+}
+
+#[inline]
+fn wrap(b: u128) -> f128 {
+    f128::from_bits(b)
+}
+
+/// Raises the exceptions of an operation and returns its result.
+#[inline]
+fn finish((b, raised): (u128, FpFlags)) -> u128 {
+    flags::raise(raised);
+    b
+}
+
+/// Whether `x` is a signalling NaN.
+#[inline]
+pub fn is_signaling(x: f128) -> bool {
+    is_snan(bits(x))
+}
+
+/// The quieted NaN an operation on `x` and `y` returns, one of which must be a NaN, raising the
+/// invalid exception for signalling NaNs.
+pub fn nan_result(x: f128, y: f128) -> f128 {
+    wrap(finish(propagate_nan(bits(x), bits(y), false)))
+}
+
+pub fn add(x: f128, y: f128, mode: RoundingMode) -> f128 {
+    let (a, b) = (bits(x), bits(y));
+    if is_nan(a) || is_nan(b) {
+        return wrap(finish(propagate_nan(a, b, true)));
+    }
+    wrap(finish(add_bits(a, b, mode)))
+}
+
+pub fn sub(x: f128, y: f128, mode: RoundingMode) -> f128 {
+    let (a, b) = (bits(x), bits(y));
+    if is_nan(a) || is_nan(b) {
+        return wrap(finish(propagate_nan(a, b, true)));
+    }
+    wrap(finish(add_bits(a, b ^ SIGN_MASK, mode)))
+}
+
+pub fn mul(x: f128, y: f128, mode: RoundingMode) -> f128 {
+    let (a, b) = (bits(x), bits(y));
+    if is_nan(a) || is_nan(b) {
+        return wrap(finish(propagate_nan(a, b, false)));
+    }
+    wrap(finish(mul_bits(a, b, mode)))
+}
+
+pub fn div(x: f128, y: f128, mode: RoundingMode) -> f128 {
+    let (a, b) = (bits(x), bits(y));
+    if is_nan(a) || is_nan(b) {
+        return wrap(finish(propagate_nan(a, b, false)));
+    }
+    wrap(finish(div_bits(a, b, mode)))
+}
+
+pub fn sqrt(x: f128, mode: RoundingMode) -> f128 {
+    let a = bits(x);
+    if is_nan(a) {
+        return wrap(finish(propagate_nan(a, a, false)));
+    }
+    wrap(finish(sqrt_bits(a, mode)))
+}
+
+pub fn from_u128(x: u128, mode: RoundingMode) -> f128 {
+    wrap(finish(from_int(false, x, mode)))
+}
+
+pub fn from_i128(x: i128, mode: RoundingMode) -> f128 {
+    wrap(finish(from_int(x < 0, x.unsigned_abs(), mode)))
+}
+
+pub fn from_parts(negative: bool, exponent: i32, significand: u128, mode: RoundingMode) -> f128 {
+    wrap(finish(from_scaled_int(negative, significand, exponent, mode)))
+}
+
+/// `from_parts` without raising anything: the result and the exceptions it would raise.
+pub fn from_parts_quietly(
+    negative: bool,
+    exponent: i32,
+    significand: u128,
+    mode: RoundingMode,
+) -> (f128, FpFlags) {
+    let (b, raised) = from_scaled_int(negative, significand, exponent, mode);
+    (wrap(b), raised)
+}
+
+pub fn to_f32(x: f128, mode: RoundingMode) -> f32 {
+    f32::from_bits(finish(to_narrow(BINARY32, bits(x), mode)) as u32)
+}
+
+pub fn to_f64(x: f128, mode: RoundingMode) -> f64 {
+    f64::from_bits(finish(to_narrow(BINARY64, bits(x), mode)) as u64)
+}
+
+pub fn f128_add(x: f128, y: f128) -> f128 {
+    add(x, y, RoundingMode::NearestEven)
+}
+
+pub fn f128_sub(x: f128, y: f128) -> f128 {
+    sub(x, y, RoundingMode::NearestEven)
+}
+
+pub fn f128_mul(x: f128, y: f128) -> f128 {
+    mul(x, y, RoundingMode::NearestEven)
+}
+
+pub fn f128_div(x: f128, y: f128) -> f128 {
+    div(x, y, RoundingMode::NearestEven)
+}
+
+pub fn gtq(lhs: f128, rhs: f128) -> u8 {
+    (cmp_bits(bits(lhs), bits(rhs)) == Some(Ordering::Greater)) as u8
+}
+
+pub fn gteq(lhs: f128, rhs: f128) -> u8 {
+    match cmp_bits(bits(lhs), bits(rhs)) {
+        Some(Ordering::Greater) | Some(Ordering::Equal) => 1,
+        _ => 0,
+    }
+}
+
+pub fn ltq(lhs: f128, rhs: f128) -> u8 {
+    (cmp_bits(bits(lhs), bits(rhs)) == Some(Ordering::Less)) as u8
+}
+
+pub fn lteq(lhs: f128, rhs: f128) -> u8 {
+    match cmp_bits(bits(lhs), bits(rhs)) {
+        Some(Ordering::Less) | Some(Ordering::Equal) => 1,
+        _ => 0,
+    }
+}
+
+pub fn eqq(lhs: f128, rhs: f128) -> u8 {
+    (cmp_bits(bits(lhs), bits(rhs)) == Some(Ordering::Equal)) as u8
+}
+
+pub fn neqq(lhs: f128, rhs: f128) -> u8 {
+    (cmp_bits(bits(lhs), bits(rhs)) != Some(Ordering::Equal)) as u8
+}
+
+macro_rules! signed_conversions {
+    ($($ty:ty, $to:ident, $from:ident);*) => ($(
+        pub fn $from(x: $ty) -> f128 {
+            from_i128(x as i128, RoundingMode::NearestEven)
+        }
+
+        pub fn $to(x: f128) -> $ty {
+            to_signed(bits(x), <$ty>::MIN as i128, <$ty>::MAX as i128) as $ty
+        }
+    )*)
+}
+
+macro_rules! unsigned_conversions {
+    ($($ty:ty, $to:ident, $from:ident);*) => ($(
+        pub fn $from(x: $ty) -> f128 {
+            from_u128(x as u128, RoundingMode::NearestEven)
+        }
+
+        pub fn $to(x: f128) -> $ty {
+            to_unsigned(bits(x), <$ty>::MAX as u128) as $ty
+        }
+    )*)
+}
+
+signed_conversions! {
+    isize, f128_to_isize, isize_to_f128;
+    i8, f128_to_i8, i8_to_f128;
+    i16, f128_to_i16, i16_to_f128;
+    i32, f128_to_i32, i32_to_f128;
+    i64, f128_to_i64, i64_to_f128;
+    i128, f128_to_i128, i128_to_f128
+}
+
+unsigned_conversions! {
+    usize, f128_to_usize, usize_to_f128;
+    u8, f128_to_u8, u8_to_f128;
+    u16, f128_to_u16, u16_to_f128;
+    u32, f128_to_u32, u32_to_f128;
+    u64, f128_to_u64, u64_to_f128;
+    u128, f128_to_u128, u128_to_f128
+}
+
+pub fn f32_to_f128(x: f32) -> f128 {
+    wrap(from_narrow(BINARY32, x.to_bits() as u128))
+}
+
+pub fn f128_to_f32(x: f128) -> f32 {
+    to_f32(x, RoundingMode::NearestEven)
+}
+
+pub fn f64_to_f128(x: f64) -> f128 {
+    wrap(from_narrow(BINARY64, x.to_bits() as u128))
+}
+
+pub fn f128_to_f64(x: f128) -> f64 {
+    to_f64(x, RoundingMode::NearestEven)
+}
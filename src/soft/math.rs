@@ -0,0 +1,1314 @@
+//! The libquadmath functions `src/f128.c` wraps, for builds without it: rounding to integers,
+//! remainders, scaling and decomposition, which are exact and computed on the bits, and the
+//! elementary functions, which are evaluated with a 256-bit significand and then rounded once.
+//!
+//! Like the C functions they stand in for, they round to nearest whatever the current
+//! `RoundingMode`, and follow C99's Annex F for zeros, infinities and NaNs. The elementary
+//! functions are correctly rounded except in cases far rarer than libquadmath's own errors, which
+//! reach about an ulp.
+
+use f128_t::f128;
+use libc::{c_int, c_long, c_longlong};
+use rounding::RoundingMode;
+use soft::{self, mul_wide};
+use std::cmp::Ordering;
+use std::f64::consts;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+const SIGN_MASK: u128 = 1 << 127;
+const EXP_MASK: u128 = 0x7fff << 112;
+const FRAC_MASK: u128 = (1 << 112) - 1;
+const BIAS: i32 = 16383;
+
+const NEAREST: RoundingMode = RoundingMode::NearestEven;
+
+/// A binary floating-point number with a 256-bit significand: `(-1)^neg * (hi:lo) * 2^(exp - 255)`
+/// with the top bit of `hi` set, or zero if `hi` is zero. Its exponent range is wide enough that
+/// nothing overflows or underflows before the result is rounded to `f128`.
+///
+/// The operations truncate rather than round, except that sums remember discarded bits in the
+/// lowest one, which keeps `fmaq_f` correctly rounded.
+#[derive(Clone, Copy)]
+struct Wide {
+    neg: bool,
+    exp: i32,
+    hi: u128,
+    lo: u128,
+}
+
+/// Series are summed until their terms fall this many bits below the sum.
+const PRECISION: i32 = 260;
+
+const ZERO: Wide = Wide::constant(0, 0, 0);
+const ONE: Wide = Wide::constant(0, 1 << 127, 0);
+const TWO: Wide = Wide::constant(1, 1 << 127, 0);
+const LN_2: Wide = Wide::constant(
+    -1,
+    0xb172_17f7_d1cf_79ab_c9e3_b398_03f2_f6af,
+    0x40f3_4326_7298_b62d_8a0d_175b_8baa_fa2c,
+);
+const FRAC_PI_2: Wide = Wide::constant(
+    0,
+    0xc90f_daa2_2168_c234_c4c6_628b_80dc_1cd1,
+    0x2902_4e08_8a67_cc74_020b_bea6_3b13_9b22,
+);
+const LOG2_E: Wide = Wide::constant(
+    0,
+    0xb8aa_3b29_5c17_f0bb_be87_fed0_691d_3e88,
+    0xeb57_7aa8_dd69_5a58_8b25_166c_d1a1_3248,
+);
+const LOG10_E: Wide = Wide::constant(
+    -2,
+    0xde5b_d8a9_3728_7195_355b_aaaf_ad33_dc32,
+    0x3ee3_4602_45c9_a202_3a3f_2d44_f78e_a53c,
+);
+
+/// Shifts the 256-bit `(hi, lo)` left by `n < 256` bits.
+#[inline]
+fn shl(hi: u128, lo: u128, n: u32) -> (u128, u128) {
+    match n {
+        0 => (hi, lo),
+        1..=127 => (hi << n | lo >> (128 - n), lo << n),
+        _ => (lo << (n - 128), 0),
+    }
+}
+
+/// Shifts the 256-bit `(hi, lo)` right by `n` bits, OR-ing any bits shifted out into the lowest
+/// bit.
+#[inline]
+fn shr_jam(hi: u128, lo: u128, n: u32) -> (u128, u128) {
+    let (hi, lo, lost) = match n {
+        0 => return (hi, lo),
+        1..=127 => (hi >> n, lo >> n | hi << (128 - n), lo << (128 - n) != 0),
+        128 => (0, hi, lo != 0),
+        129..=255 => (0, hi >> (n - 128), hi << (256 - n) != 0 || lo != 0),
+        _ => (0, 0, hi != 0 || lo != 0),
+    };
+    (hi, lo | lost as u128)
+}
+
+impl Wide {
+    const fn constant(exp: i32, hi: u128, lo: u128) -> Wide {
+        Wide {
+            neg: false,
+            exp,
+            hi,
+            lo,
+        }
+    }
+
+    /// `(-1)^neg * (hi:lo) * 2^(exp - 255)`, normalised.
+    fn new(neg: bool, exp: i32, hi: u128, lo: u128) -> Wide {
+        let shift = if hi != 0 {
+            hi.leading_zeros()
+        } else if lo != 0 {
+            128 + lo.leading_zeros()
+        } else {
+            return ZERO;
+        };
+        let (hi, lo) = shl(hi, lo, shift);
+        Wide {
+            neg,
+            exp: exp - shift as i32,
+            hi,
+            lo,
+        }
+    }
+
+    /// `(-1)^neg * mag * 2^exp`.
+    fn from_int(neg: bool, mag: u128, exp: i32) -> Wide {
+        Wide::new(neg, exp + 127, mag, 0)
+    }
+
+    /// The finite `x`, exactly. Both zeros become `ZERO`.
+    fn from_f128(x: f128) -> Wide {
+        let (neg, exp, sig) = x.decode();
+        Wide::from_int(neg, sig, exp)
+    }
+
+    /// The normal `x`.
+    fn from_f64(x: f64) -> Wide {
+        let bits = x.to_bits();
+        let sig = bits & ((1 << 52) - 1) | 1 << 52;
+        Wide::from_int(x < 0.0, sig as u128, (bits >> 52 & 0x7ff) as i32 - 1075)
+    }
+
+    /// `self` rounded to the nearest `f128`.
+    fn to_f128(self) -> f128 {
+        let sig = self.hi | (self.lo != 0) as u128;
+        soft::from_parts_quietly(self.neg, self.exp.saturating_sub(127), sig, NEAREST).0
+    }
+
+    /// An estimate of `self` good to 53 bits, for choosing between methods.
+    fn to_f64(self) -> f64 {
+        let m = (self.hi >> 64) as u64 as f64 * 2f64.powi(self.exp.saturating_sub(63));
+        if self.neg {
+            -m
+        } else {
+            m
+        }
+    }
+
+    #[inline]
+    fn is_zero(self) -> bool {
+        self.hi == 0
+    }
+
+    #[inline]
+    fn abs(self) -> Wide {
+        Wide { neg: false, ..self }
+    }
+
+    /// `self * 2^n`.
+    #[inline]
+    fn scale(self, n: i32) -> Wide {
+        if self.is_zero() {
+            self
+        } else {
+            Wide {
+                exp: self.exp + n,
+                ..self
+            }
+        }
+    }
+
+    /// `self / d`.
+    fn div_small(self, d: u64) -> Wide {
+        let limbs = [
+            (self.hi >> 64) as u64,
+            self.hi as u64,
+            (self.lo >> 64) as u64,
+            self.lo as u64,
+            0,
+        ];
+        let mut q = [0u64; 5];
+        let mut rem = 0u128;
+        for (q, &limb) in q.iter_mut().zip(limbs.iter()) {
+            let cur = rem << 64 | limb as u128;
+            *q = (cur / d as u128) as u64;
+            rem = cur % d as u128;
+        }
+        // The quotient of the top four limbs has at most 64 leading zeros; the fifth refills
+        // the bits they leave.
+        let hi = (q[0] as u128) << 64 | q[1] as u128;
+        let lo = (q[2] as u128) << 64 | q[3] as u128;
+        let shift = if hi != 0 {
+            hi.leading_zeros()
+        } else {
+            return Wide::new(self.neg, self.exp, hi, lo);
+        };
+        if shift == 0 {
+            return Wide { hi, lo, ..self };
+        }
+        let (hi, lo) = shl(hi, lo, shift);
+        Wide {
+            neg: self.neg,
+            exp: self.exp - shift as i32,
+            hi,
+            lo: lo | (q[4] as u128) << 64 >> (128 - shift),
+        }
+    }
+
+    /// `1 / self` by Newton's iteration from a double-precision estimate, which doubles the
+    /// number of correct bits each step.
+    fn recip(self) -> Wide {
+        let m = Wide {
+            neg: false,
+            exp: 0,
+            ..self
+        };
+        let mut y = Wide::from_f64(1.0 / m.to_f64());
+        for _ in 0..3 {
+            y = y + y * (ONE - m * y);
+        }
+        Wide { neg: self.neg, ..y }.scale(-self.exp)
+    }
+
+    /// The square root of the non-negative `self`, from Newton's iteration for its reciprocal.
+    fn sqrt(self) -> Wide {
+        if self.is_zero() {
+            return ZERO;
+        }
+        // self = m * 2^(2 * half) with m in [1, 4).
+        let half = self.exp >> 1;
+        let m = Wide {
+            exp: self.exp - 2 * half,
+            ..self
+        };
+        let mut y = Wide::from_f64(1.0 / m.to_f64().sqrt());
+        for _ in 0..3 {
+            y = y + (y * (ONE - m * y * y)).scale(-1);
+        }
+        (m * y).scale(half)
+    }
+}
+
+impl Neg for Wide {
+    type Output = Wide;
+
+    #[inline]
+    fn neg(self) -> Wide {
+        if self.is_zero() {
+            self
+        } else {
+            Wide {
+                neg: !self.neg,
+                ..self
+            }
+        }
+    }
+}
+
+impl Add for Wide {
+    type Output = Wide;
+
+    fn add(self, other: Wide) -> Wide {
+        if other.is_zero() {
+            return self;
+        }
+        if self.is_zero() {
+            return other;
+        }
+        let (a, b) = if (self.exp, self.hi, self.lo) >= (other.exp, other.hi, other.lo) {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let (hi, lo) = shr_jam(b.hi, b.lo, (a.exp - b.exp) as u32);
+        if a.neg == b.neg {
+            let (lo, carry) = a.lo.overflowing_add(lo);
+            let (hi, c1) = a.hi.overflowing_add(hi);
+            let (hi, c2) = hi.overflowing_add(carry as u128);
+            if c1 || c2 {
+                let (hi, lo) = shr_jam(hi, lo, 1);
+                return Wide {
+                    neg: a.neg,
+                    exp: a.exp + 1,
+                    hi: hi | 1 << 127,
+                    lo,
+                };
+            }
+            Wide { hi, lo, ..a }
+        } else {
+            let (lo, borrow) = a.lo.overflowing_sub(lo);
+            Wide::new(a.neg, a.exp, a.hi - hi - borrow as u128, lo)
+        }
+    }
+}
+
+impl Sub for Wide {
+    type Output = Wide;
+
+    #[inline]
+    fn sub(self, other: Wide) -> Wide {
+        self + -other
+    }
+}
+
+impl Mul for Wide {
+    type Output = Wide;
+
+    fn mul(self, other: Wide) -> Wide {
+        if self.is_zero() || other.is_zero() {
+            return ZERO;
+        }
+        // The 512-bit product as four 128-bit words w3:w2:w1:w0, of which w3:w2 are kept.
+        let (hh1, hh0) = mul_wide(self.hi, other.hi);
+        let (hl1, hl0) = mul_wide(self.hi, other.lo);
+        let (lh1, lh0) = mul_wide(self.lo, other.hi);
+        let (ll1, ll0) = mul_wide(self.lo, other.lo);
+        let (w1, c1) = hl0.overflowing_add(lh0);
+        let (w1, c2) = w1.overflowing_add(ll1);
+        let (w2, d1) = hh0.overflowing_add(hl1);
+        let (w2, d2) = w2.overflowing_add(lh1);
+        let (w2, d3) = w2.overflowing_add(c1 as u128 + c2 as u128);
+        let w3 = hh1 + d1 as u128 + d2 as u128 + d3 as u128;
+        let sticky = (w1 != 0 || ll0 != 0) as u128;
+        Wide::new(self.neg != other.neg, self.exp + other.exp + 1, w3, w2 | sticky)
+    }
+}
+
+impl Div for Wide {
+    type Output = Wide;
+
+    #[inline]
+    fn div(self, other: Wide) -> Wide {
+        self * other.recip()
+    }
+}
+
+/// `e^x - 1` for `|x|` up to about 0.35: the Taylor series at `x / 256`, squared back up as
+/// `(1 + e)^2 - 1 = e * (e + 2)`, which keeps the relative precision of small results.
+fn expm1_reduced(x: Wide) -> Wide {
+    let t = x.scale(-8);
+    let mut sum = t;
+    let mut term = t;
+    let mut n = 1;
+    while !term.is_zero() && term.exp > sum.exp - PRECISION {
+        n += 1;
+        term = (term * t).div_small(n);
+        sum = sum + term;
+    }
+    for _ in 0..8 {
+        sum = sum * (sum + TWO);
+    }
+    sum
+}
+
+/// `e^x`, reduced to `expm1_reduced` by a multiple of ln 2. Results far outside the range of
+/// `f128` are only accurate enough to round to infinity or zero.
+fn exp(x: Wide) -> Wide {
+    let estimate = x.to_f64();
+    if estimate > 12_000.0 {
+        return ONE.scale(20_000);
+    }
+    if estimate < -12_000.0 {
+        return ONE.scale(-20_000);
+    }
+    let k = (estimate * consts::LOG2_E).round();
+    let r = x - Wide::from_int(k < 0.0, k.abs() as u128, 0) * LN_2;
+    (ONE + expm1_reduced(r)).scale(k as i32)
+}
+
+/// `e^x - 1`, without the cancellation of `exp(x) - 1` for small `x`.
+fn expm1(x: Wide) -> Wide {
+    if x.to_f64().abs() < 0.34 {
+        expm1_reduced(x)
+    } else {
+        exp(x) - ONE
+    }
+}
+
+/// `atanh(s) = s + s^3/3 + s^5/5 + ...` for `|s|` up to about 0.18.
+fn atanh_series(s: Wide) -> Wide {
+    let s2 = s * s;
+    let mut sum = s;
+    let mut power = s;
+    let mut n = 1;
+    loop {
+        power = power * s2;
+        n += 2;
+        let term = power.div_small(n);
+        if term.is_zero() || term.exp < sum.exp - PRECISION {
+            return sum;
+        }
+        sum = sum + term;
+    }
+}
+
+/// `ln(x)` for positive `x`: with `x = m * 2^e` and `m` within a factor of sqrt(2) of 1,
+/// `e ln 2 + 2 atanh((m - 1) / (m + 1))`.
+fn ln(x: Wide) -> Wide {
+    let mut e = x.exp;
+    let mut m = Wide { exp: 0, ..x };
+    if m.to_f64() > consts::SQRT_2 {
+        m.exp = -1;
+        e += 1;
+    }
+    let ln_m = atanh_series((m - ONE) / (m + ONE)).scale(1);
+    ln_m + Wide::from_int(e < 0, e.unsigned_abs() as u128, 0) * LN_2
+}
+
+/// `ln(1 + x)` for `x > -1`, without the cancellation of `ln(1 + x)` for small `x`.
+fn ln_1p(x: Wide) -> Wide {
+    let estimate = x.to_f64();
+    if estimate > -0.29 && estimate < 0.41 {
+        atanh_series(x / (x + TWO)).scale(1)
+    } else {
+        ln(ONE + x)
+    }
+}
+
+/// `atan(x)`: arguments beyond 1 are inverted with `atan(x) = pi/2 - atan(1/x)`, and the rest
+/// halved three times with `atan(x) = 2 atan(x / (1 + sqrt(1 + x^2)))` before the Taylor series.
+fn atan(x: Wide) -> Wide {
+    let mut a = x.abs();
+    let invert = a.to_f64() > 1.0;
+    if invert {
+        a = a.recip();
+    }
+    for _ in 0..3 {
+        a = a / (ONE + (ONE + a * a).sqrt());
+    }
+    let a2 = a * a;
+    let mut sum = a;
+    let mut power = a;
+    let mut n = 1;
+    loop {
+        power = -(power * a2);
+        n += 2;
+        let term = power.div_small(n);
+        if term.is_zero() || term.exp < sum.exp - PRECISION {
+            break;
+        }
+        sum = sum + term;
+    }
+    let mut result = sum.scale(3);
+    if invert {
+        result = FRAC_PI_2 - result;
+    }
+    if x.neg {
+        -result
+    } else {
+        result
+    }
+}
+
+/// The Taylor series of `sin(r)` and `cos(r)` for `|r| <= pi/4`.
+fn sin_cos_series(r: Wide) -> (Wide, Wide) {
+    let r2 = r * r;
+    let series = |first: Wide, mut n: u64| {
+        let mut sum = first;
+        let mut term = first;
+        while !term.is_zero() && term.exp > sum.exp - PRECISION {
+            term = -(term * r2).div_small((n + 1) * (n + 2));
+            n += 2;
+            sum = sum + term;
+        }
+        sum
+    };
+    (series(r, 1), series(ONE, 0))
+}
+
+/// 64 bits of 2/pi from bit `start` on, counting the first bit after the point as 1.
+fn two_over_pi_bits(start: usize) -> u64 {
+    let (word, offset) = ((start - 1) / 64, (start - 1) % 64);
+    if offset == 0 {
+        TWO_OVER_PI[word]
+    } else {
+        TWO_OVER_PI[word] << offset | TWO_OVER_PI[word + 1] >> (64 - offset)
+    }
+}
+
+/// Reduces the finite `x` to `n` and `r` such that `x = n pi/2 + r` with `|r| <= pi/4`, `n` being
+/// known only modulo 4.
+///
+/// This is Payne and Hanek's method: `x * 2/pi` only needs the bits of 2/pi that contribute to
+/// its value modulo 4, from a little above the lowest bit of `x` down to far enough below to
+/// keep `r` accurate however close `x` comes to a multiple of pi/2.
+fn reduce(x: f128) -> (u32, Wide) {
+    if x.to_bits() & !SIGN_MASK <= f128::FRAC_PI_4.to_bits() {
+        return (0, Wide::from_f128(x));
+    }
+    let (neg, exp, sig) = x.decode();
+    // x * 2/pi is the sum of sig * 2^(exp - i) over the bits i of 2/pi; those with
+    // exp - i >= 2 only add multiples of 4. Take 512 bits from the first one that matters.
+    let first = (exp - 1).max(1) as usize;
+    let mut product = [0u64; 10];
+    for k in 0..8 {
+        let bits = two_over_pi_bits(first + 64 * (7 - k)) as u128;
+        let low = bits * sig as u64 as u128 + product[k] as u128;
+        let high = bits * (sig >> 64) as u64 as u128 + product[k + 1] as u128 + (low >> 64);
+        product[k] = low as u64;
+        product[k + 1] = high as u64;
+        product[k + 2] = (high >> 64) as u64;
+    }
+    // The product is x * 2/pi modulo 4 with this many bits after the point.
+    let point = (first as i32 + 511 - exp) as usize;
+    let bits_at = |pos: usize| {
+        let (limb, offset) = (pos / 64, pos % 64);
+        let low = product[limb] >> offset;
+        match product.get(limb + 1) {
+            Some(&high) if offset != 0 => low | high << (64 - offset),
+            _ => low,
+        }
+    };
+    let mut n = bits_at(point) as u32 & 3;
+    let mut fraction = [0u64; 6];
+    for (k, limb) in fraction.iter_mut().enumerate() {
+        *limb = bits_at(point - 64 * (k + 1));
+    }
+    // Round to the nearest multiple of pi/2, leaving a negative remainder if that rounds up.
+    let up = fraction[0] >> 63 != 0;
+    if up {
+        n += 1;
+        let mut borrow = true;
+        for limb in fraction.iter_mut().rev() {
+            let (value, b) = (!*limb).overflowing_add(borrow as u64);
+            *limb = value;
+            borrow = b;
+        }
+    }
+    let join = |a: u64, b: u64| (a as u128) << 64 | b as u128;
+    let high = Wide::new(up, -1, join(fraction[0], fraction[1]), join(fraction[2], fraction[3]));
+    let low = Wide::new(up, -257, join(fraction[4], fraction[5]), 0);
+    let r = (high + low) * FRAC_PI_2;
+    if neg {
+        (n.wrapping_neg() & 3, -r)
+    } else {
+        (n & 3, r)
+    }
+}
+
+#[inline]
+fn is_negative(x: f128) -> bool {
+    x.to_bits() & SIGN_MASK != 0
+}
+
+#[inline]
+fn is_nan(x: f128) -> bool {
+    x.to_bits() & !SIGN_MASK > EXP_MASK
+}
+
+#[inline]
+fn is_infinite(x: f128) -> bool {
+    x.to_bits() & !SIGN_MASK == EXP_MASK
+}
+
+#[inline]
+fn is_zero(x: f128) -> bool {
+    x.to_bits() & !SIGN_MASK == 0
+}
+
+#[inline]
+fn abs(x: f128) -> f128 {
+    f128::from_bits(x.to_bits() & !SIGN_MASK)
+}
+
+/// `x` with the sign set if `negative`.
+#[inline]
+fn with_sign(x: f128, negative: bool) -> f128 {
+    f128::from_bits(x.to_bits() & !SIGN_MASK | (negative as u128) << 127)
+}
+
+/// Compares the magnitudes of `x` and `y`, neither of which is NaN.
+#[inline]
+fn cmp_abs(x: f128, y: f128) -> Ordering {
+    (x.to_bits() & !SIGN_MASK).cmp(&(y.to_bits() & !SIGN_MASK))
+}
+
+/// The unbiased exponent of `x`, below -16382 for zeros and subnormals.
+#[inline]
+fn exponent(x: f128) -> i32 {
+    ((x.to_bits() & EXP_MASK) >> 112) as i32 - BIAS
+}
+
+/// Whether the finite `x` is an integer.
+fn is_integer(x: f128) -> bool {
+    truncq_f(x).to_bits() == x.to_bits()
+}
+
+/// Whether the finite `x` is an odd integer.
+fn is_odd_integer(x: f128) -> bool {
+    let e = exponent(x);
+    e >= 0 && e <= 112 && is_integer(x) && (x.to_bits() | 1 << 112) >> (112 - e) & 1 != 0
+}
+
+/// The NaN for an invalid operation.
+#[inline]
+fn invalid() -> f128 {
+    f128::NAN
+}
+
+pub fn truncq_f(x: f128) -> f128 {
+    match exponent(x) {
+        _ if is_nan(x) => soft::nan_result(x, x),
+        e if e >= 112 => x,
+        e if e < 0 => with_sign(f128::ZERO, is_negative(x)),
+        e => f128::from_bits(x.to_bits() & !((1 << (112 - e)) - 1)),
+    }
+}
+
+/// `x` truncated to an integer, then moved one further from zero if `away` says so given its
+/// sign, how the dropped fraction compares with one half and whether the truncation is odd.
+fn to_integer<F: Fn(bool, Ordering, bool) -> bool>(x: f128, away: F) -> f128 {
+    let t = truncq_f(x);
+    if is_nan(x) || t.to_bits() == x.to_bits() {
+        return t;
+    }
+    let fraction = abs(soft::sub(x, t, NEAREST));
+    let half = fraction.to_bits().cmp(&0x3ffe_0000_0000_0000_0000_0000_0000_0000);
+    let odd = !is_zero(t) && is_odd_integer(t);
+    if away(is_negative(x), half, odd) {
+        soft::add(t, with_sign(f128::ONE, is_negative(x)), NEAREST)
+    } else {
+        t
+    }
+}
+
+pub fn floorq_f(x: f128) -> f128 {
+    to_integer(x, |negative, _, _| negative)
+}
+
+pub fn ceilq_f(x: f128) -> f128 {
+    to_integer(x, |negative, _, _| !negative)
+}
+
+pub fn roundq_f(x: f128) -> f128 {
+    to_integer(x, |_, half, _| half != Ordering::Less)
+}
+
+pub fn nearbyintq_f(x: f128) -> f128 {
+    to_integer(x, |_, half, odd| half == Ordering::Greater || half == Ordering::Equal && odd)
+}
+
+pub fn rintq_f(x: f128) -> f128 {
+    nearbyintq_f(x)
+}
+
+pub fn llrintq_f(x: f128) -> c_longlong {
+    soft::f128_to_i64(nearbyintq_f(x)) as c_longlong
+}
+
+pub fn llroundq_f(x: f128) -> c_longlong {
+    soft::f128_to_i64(roundq_f(x)) as c_longlong
+}
+
+pub fn modfq_f(x: f128, integral: &mut f128) -> f128 {
+    *integral = truncq_f(x);
+    if is_nan(x) {
+        *integral
+    } else if is_infinite(x) {
+        with_sign(f128::ZERO, is_negative(x))
+    } else {
+        with_sign(soft::sub(x, *integral, NEAREST), is_negative(x))
+    }
+}
+
+pub fn frexpq_f(x: f128, exp: &mut c_int) -> f128 {
+    *exp = 0;
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_infinite(x) || is_zero(x) {
+        return x;
+    }
+    let (negative, e, sig) = x.decode();
+    let len = 128 - sig.leading_zeros() as i32;
+    *exp = e + len;
+    soft::from_parts(negative, -len, sig, NEAREST)
+}
+
+pub fn ldexpq_f(x: f128, n: c_int) -> f128 {
+    x.scale_by_pow2(n)
+}
+
+pub fn scalbnq_f(x: f128, n: c_int) -> f128 {
+    x.scale_by_pow2(n)
+}
+
+pub fn scalblnq_f(x: f128, n: c_long) -> f128 {
+    let n = n
+        .max(i32::min_value() as c_long)
+        .min(i32::max_value() as c_long);
+    x.scale_by_pow2(n as i32)
+}
+
+pub fn ilogbq_f(x: f128) -> c_int {
+    if is_nan(x) || is_zero(x) {
+        return c_int::min_value();
+    }
+    if is_infinite(x) {
+        return c_int::max_value();
+    }
+    let (_, e, sig) = x.decode();
+    e + 127 - sig.leading_zeros() as i32
+}
+
+pub fn logbq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        soft::nan_result(x, x)
+    } else if is_infinite(x) {
+        f128::INFINITY
+    } else if is_zero(x) {
+        f128::NEG_INFINITY
+    } else {
+        soft::from_i128(ilogbq_f(x) as i128, NEAREST)
+    }
+}
+
+pub fn copysignq_f(x: f128, y: f128) -> f128 {
+    with_sign(x, is_negative(y))
+}
+
+pub fn fdimq_f(x: f128, y: f128) -> f128 {
+    if is_nan(x) || is_nan(y) {
+        soft::nan_result(x, y)
+    } else if soft::gtq(x, y) != 0 {
+        soft::sub(x, y, NEAREST)
+    } else {
+        f128::ZERO
+    }
+}
+
+pub fn fmaxq_f(x: f128, y: f128) -> f128 {
+    if soft::gteq(x, y) != 0 {
+        x
+    } else if soft::ltq(x, y) != 0 {
+        y
+    } else if soft::is_signaling(x) || soft::is_signaling(y) {
+        soft::f128_add(x, y)
+    } else if is_nan(y) {
+        x
+    } else {
+        y
+    }
+}
+
+pub fn fminq_f(x: f128, y: f128) -> f128 {
+    if soft::lteq(x, y) != 0 {
+        x
+    } else if soft::gtq(x, y) != 0 {
+        y
+    } else if soft::is_signaling(x) || soft::is_signaling(y) {
+        soft::f128_add(x, y)
+    } else if is_nan(y) {
+        x
+    } else {
+        y
+    }
+}
+
+pub fn nextafterq_f(x: f128, y: f128) -> f128 {
+    if is_nan(x) || is_nan(y) {
+        return soft::f128_add(x, y);
+    }
+    if soft::eqq(x, y) != 0 {
+        return y;
+    }
+    if is_zero(x) {
+        return with_sign(f128::MIN_POSITIVE_SUBNORMAL, is_negative(y));
+    }
+    let toward_zero = (soft::gtq(x, y) != 0) != is_negative(x);
+    if toward_zero {
+        f128::from_bits(x.to_bits() - 1)
+    } else {
+        f128::from_bits(x.to_bits() + 1)
+    }
+}
+
+/// Divides the finite `x` by the finite non-zero `y`, with `|x| >= |y|`, as integers: the
+/// exponent that scales them, the remainder and divisor significands and the lowest bits of the
+/// quotient of their magnitudes.
+fn divide(x: f128, y: f128) -> (i32, u128, u128, u32) {
+    let (_, ex, mx) = x.decode();
+    let (_, ey, my) = y.decode();
+    // `|x| >= |y|` makes `ex >= ey`. Shift the dividend in 14 bits at a time, which keeps the
+    // partial remainder below 2^127.
+    let mut quotient = (mx / my) as u32;
+    let mut rem = mx % my;
+    let mut shift = (ex - ey) as u32;
+    while shift > 0 {
+        let n = shift.min(14);
+        let t = rem << n;
+        quotient = quotient << n | (t / my) as u32;
+        rem = t % my;
+        shift -= n;
+    }
+    (ey, rem, my, quotient)
+}
+
+pub fn fmodq_f(x: f128, y: f128) -> f128 {
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(x, y);
+    }
+    if is_infinite(x) || is_zero(y) {
+        return invalid();
+    }
+    if is_infinite(y) || cmp_abs(x, y) == Ordering::Less {
+        return x;
+    }
+    let (exp, rem, _, _) = divide(x, y);
+    soft::from_parts(is_negative(x), exp, rem, NEAREST)
+}
+
+pub fn remquoq_f(x: f128, y: f128, quo: &mut c_int) -> f128 {
+    *quo = 0;
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(x, y);
+    }
+    if is_infinite(x) || is_zero(y) {
+        return invalid();
+    }
+    if is_infinite(y) {
+        return x;
+    }
+    let negative = is_negative(x);
+    let (remainder, quotient) = if cmp_abs(x, y) == Ordering::Less {
+        // The quotient rounds to 1 if |x| is more than half of |y|, and to 0 otherwise.
+        let twice = soft::add(abs(x), abs(x), NEAREST);
+        if cmp_abs(twice, y) == Ordering::Greater {
+            (with_sign(soft::sub(abs(x), abs(y), NEAREST), !negative), 1)
+        } else {
+            (x, 0)
+        }
+    } else {
+        let (exp, rem, divisor, quotient) = divide(x, y);
+        let up = match (2 * rem).cmp(&divisor) {
+            Ordering::Greater => true,
+            Ordering::Equal => quotient & 1 != 0,
+            Ordering::Less => false,
+        };
+        // The three lowest bits of the truncated quotient, plus one if it rounds up, as in C.
+        if up {
+            (soft::from_parts(!negative, exp, divisor - rem, NEAREST), (quotient & 7) + 1)
+        } else {
+            (soft::from_parts(negative, exp, rem, NEAREST), quotient & 7)
+        }
+    };
+    let quotient = quotient as c_int;
+    *quo = if negative != is_negative(y) {
+        -quotient
+    } else {
+        quotient
+    };
+    remainder
+}
+
+pub fn remainderq_f(x: f128, y: f128) -> f128 {
+    remquoq_f(x, y, &mut 0)
+}
+
+pub fn fmaq_f(x: f128, y: f128, z: f128) -> f128 {
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(x, y);
+    }
+    let negative = is_negative(x) != is_negative(y);
+    if is_infinite(x) || is_infinite(y) {
+        if is_zero(x) || is_zero(y) || is_infinite(z) && is_negative(z) != negative {
+            return invalid();
+        }
+        return if is_nan(z) {
+            soft::nan_result(z, z)
+        } else {
+            with_sign(f128::INFINITY, negative)
+        };
+    }
+    if is_nan(z) {
+        return soft::nan_result(z, z);
+    }
+    if is_infinite(z) {
+        return z;
+    }
+    if is_zero(x) || is_zero(y) {
+        return soft::add(with_sign(f128::ZERO, negative), z, NEAREST);
+    }
+    // The product is exact, and the sum keeps every bit that can affect its rounding.
+    let sum = Wide::from_f128(x) * Wide::from_f128(y) + Wide::from_f128(z);
+    sum.to_f128()
+}
+
+pub fn expq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        soft::nan_result(x, x)
+    } else if is_infinite(x) {
+        if is_negative(x) {
+            f128::ZERO
+        } else {
+            x
+        }
+    } else {
+        exp(Wide::from_f128(x)).to_f128()
+    }
+}
+
+pub fn exp2q_f(x: f128) -> f128 {
+    if is_nan(x) || is_infinite(x) {
+        expq_f(x)
+    } else {
+        exp(Wide::from_f128(x) * LN_2).to_f128()
+    }
+}
+
+pub fn expm1q_f(x: f128) -> f128 {
+    if is_nan(x) {
+        soft::nan_result(x, x)
+    } else if is_infinite(x) {
+        if is_negative(x) {
+            -f128::ONE
+        } else {
+            x
+        }
+    } else if is_zero(x) {
+        x
+    } else {
+        expm1(Wide::from_f128(x)).to_f128()
+    }
+}
+
+/// The special cases of the logarithms, or `None` if `x` is positive and finite.
+fn log_special(x: f128) -> Option<f128> {
+    if is_nan(x) {
+        Some(soft::nan_result(x, x))
+    } else if is_zero(x) {
+        Some(f128::NEG_INFINITY)
+    } else if is_negative(x) {
+        Some(invalid())
+    } else if is_infinite(x) {
+        Some(x)
+    } else if x.to_bits() == f128::ONE.to_bits() {
+        Some(f128::ZERO)
+    } else {
+        None
+    }
+}
+
+pub fn logq_f(x: f128) -> f128 {
+    log_special(x).unwrap_or_else(|| ln(Wide::from_f128(x)).to_f128())
+}
+
+pub fn log2q_f(x: f128) -> f128 {
+    log_special(x).unwrap_or_else(|| (ln(Wide::from_f128(x)) * LOG2_E).to_f128())
+}
+
+pub fn log10q_f(x: f128) -> f128 {
+    log_special(x).unwrap_or_else(|| (ln(Wide::from_f128(x)) * LOG10_E).to_f128())
+}
+
+pub fn log1pq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    match soft::f128_sub(x, -f128::ONE) {
+        _ if is_zero(x) => x,
+        d if is_zero(d) => f128::NEG_INFINITY,
+        _ if is_negative(x) && cmp_abs(x, f128::ONE) == Ordering::Greater => invalid(),
+        _ if is_infinite(x) => x,
+        _ => ln_1p(Wide::from_f128(x)).to_f128(),
+    }
+}
+
+pub fn powq_f(x: f128, y: f128) -> f128 {
+    // A signaling NaN is not ignored even where a quiet one would be.
+    if soft::is_signaling(x) || soft::is_signaling(y) {
+        return soft::nan_result(x, y);
+    }
+    if is_zero(y) || x.to_bits() == f128::ONE.to_bits() {
+        return f128::ONE;
+    }
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(x, y);
+    }
+    if is_infinite(y) {
+        return match cmp_abs(x, f128::ONE) {
+            Ordering::Equal => f128::ONE,
+            Ordering::Less if is_negative(y) => f128::INFINITY,
+            Ordering::Greater if !is_negative(y) => f128::INFINITY,
+            _ => f128::ZERO,
+        };
+    }
+    let negative = is_negative(x) && is_odd_integer(y);
+    if is_zero(x) || is_infinite(x) {
+        let huge = is_zero(x) == is_negative(y);
+        return with_sign(if huge { f128::INFINITY } else { f128::ZERO }, negative);
+    }
+    if is_negative(x) && !is_integer(y) {
+        return invalid();
+    }
+    let result = exp(Wide::from_f128(y) * ln(Wide::from_f128(x).abs())).to_f128();
+    with_sign(result, negative)
+}
+
+pub fn cbrtq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_infinite(x) || is_zero(x) {
+        return x;
+    }
+    let result = exp(ln(Wide::from_f128(x).abs()).div_small(3)).to_f128();
+    with_sign(result, is_negative(x))
+}
+
+pub fn hypotq_f(x: f128, y: f128) -> f128 {
+    if soft::is_signaling(x) || soft::is_signaling(y) {
+        return soft::nan_result(x, y);
+    }
+    if is_infinite(x) || is_infinite(y) {
+        return f128::INFINITY;
+    }
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(x, y);
+    }
+    let (x, y) = (Wide::from_f128(x), Wide::from_f128(y));
+    (x * x + y * y).sqrt().to_f128()
+}
+
+/// The special cases of the trigonometric functions, or `None` if `x` is finite and non-zero.
+fn trig_special(x: f128) -> Option<f128> {
+    if is_nan(x) {
+        Some(soft::nan_result(x, x))
+    } else if is_infinite(x) {
+        Some(invalid())
+    } else if is_zero(x) {
+        Some(x)
+    } else {
+        None
+    }
+}
+
+pub fn sinq_f(x: f128) -> f128 {
+    trig_special(x).unwrap_or_else(|| {
+        let (n, r) = reduce(x);
+        let (sin, cos) = sin_cos_series(r);
+        match n {
+            0 => sin,
+            1 => cos,
+            2 => -sin,
+            _ => -cos,
+        }
+        .to_f128()
+    })
+}
+
+pub fn cosq_f(x: f128) -> f128 {
+    if is_zero(x) {
+        return f128::ONE;
+    }
+    trig_special(x).unwrap_or_else(|| {
+        let (n, r) = reduce(x);
+        let (sin, cos) = sin_cos_series(r);
+        match n {
+            0 => cos,
+            1 => -sin,
+            2 => -cos,
+            _ => sin,
+        }
+        .to_f128()
+    })
+}
+
+pub fn sincosq_f(x: f128, sin: &mut f128, cos: &mut f128) {
+    *sin = sinq_f(x);
+    *cos = cosq_f(x);
+}
+
+pub fn tanq_f(x: f128) -> f128 {
+    trig_special(x).unwrap_or_else(|| {
+        let (n, r) = reduce(x);
+        let (sin, cos) = sin_cos_series(r);
+        if n & 1 == 0 {
+            (sin / cos).to_f128()
+        } else {
+            (-cos / sin).to_f128()
+        }
+    })
+}
+
+pub fn atanq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        soft::nan_result(x, x)
+    } else if is_infinite(x) {
+        with_sign(FRAC_PI_2.to_f128(), is_negative(x))
+    } else if is_zero(x) {
+        x
+    } else {
+        atan(Wide::from_f128(x)).to_f128()
+    }
+}
+
+pub fn atan2q_f(y: f128, x: f128) -> f128 {
+    if is_nan(x) || is_nan(y) {
+        return soft::nan_result(y, x);
+    }
+    let pi = FRAC_PI_2.scale(1);
+    let angle = if is_zero(y) || is_infinite(x) && !is_infinite(y) {
+        if is_negative(x) {
+            pi
+        } else {
+            ZERO
+        }
+    } else if is_zero(x) || is_infinite(y) && !is_infinite(x) {
+        FRAC_PI_2
+    } else if is_infinite(x) {
+        if is_negative(x) {
+            FRAC_PI_2 + FRAC_PI_2.scale(-1)
+        } else {
+            FRAC_PI_2.scale(-1)
+        }
+    } else {
+        let t = atan(Wide::from_f128(y).abs() / Wide::from_f128(x).abs());
+        if is_negative(x) {
+            pi - t
+        } else {
+            t
+        }
+    };
+    with_sign(angle.to_f128(), is_negative(y))
+}
+
+pub fn asinq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    match cmp_abs(x, f128::ONE) {
+        Ordering::Greater => invalid(),
+        Ordering::Equal => with_sign(FRAC_PI_2.to_f128(), is_negative(x)),
+        Ordering::Less if is_zero(x) => x,
+        Ordering::Less => {
+            let x = Wide::from_f128(x);
+            atan(x / ((ONE - x) * (ONE + x)).sqrt()).to_f128()
+        }
+    }
+}
+
+pub fn acosq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    match cmp_abs(x, f128::ONE) {
+        Ordering::Greater => invalid(),
+        Ordering::Equal if is_negative(x) => FRAC_PI_2.scale(1).to_f128(),
+        Ordering::Equal => f128::ZERO,
+        Ordering::Less => {
+            let x = Wide::from_f128(x);
+            atan(((ONE - x) / (ONE + x)).sqrt()).scale(1).to_f128()
+        }
+    }
+}
+
+pub fn sinhq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_infinite(x) || is_zero(x) {
+        return x;
+    }
+    // sinh|x| = (e + e / (e + 1)) / 2 with e = expm1|x|.
+    let e = expm1(Wide::from_f128(x).abs());
+    with_sign((e + e / (e + ONE)).scale(-1).to_f128(), is_negative(x))
+}
+
+pub fn coshq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_infinite(x) {
+        return f128::INFINITY;
+    }
+    let e = exp(Wide::from_f128(x).abs());
+    (e + e.recip()).scale(-1).to_f128()
+}
+
+pub fn tanhq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_zero(x) {
+        return x;
+    }
+    // Beyond 100, tanh|x| is within 2^-288 of 1.
+    if exponent(x) >= 7 {
+        return with_sign(f128::ONE, is_negative(x));
+    }
+    // tanh|x| = e / (e + 2) with e = expm1(2|x|).
+    let e = expm1(Wide::from_f128(x).abs().scale(1));
+    with_sign((e / (e + TWO)).to_f128(), is_negative(x))
+}
+
+pub fn asinhq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_infinite(x) || is_zero(x) {
+        return x;
+    }
+    // asinh|x| = ln(1 + |x| + x^2 / (1 + sqrt(1 + x^2))).
+    let a = Wide::from_f128(x).abs();
+    let a2 = a * a;
+    let result = ln_1p(a + a2 / (ONE + (ONE + a2).sqrt())).to_f128();
+    with_sign(result, is_negative(x))
+}
+
+pub fn acoshq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    if is_negative(x) || cmp_abs(x, f128::ONE) == Ordering::Less {
+        return invalid();
+    }
+    if is_infinite(x) {
+        return x;
+    }
+    // acosh x = ln(1 + t + sqrt(t (t + 2))) with t = x - 1.
+    let t = Wide::from_f128(x) - ONE;
+    ln_1p(t + (t * (t + TWO)).sqrt()).to_f128()
+}
+
+pub fn atanhq_f(x: f128) -> f128 {
+    if is_nan(x) {
+        return soft::nan_result(x, x);
+    }
+    match cmp_abs(x, f128::ONE) {
+        Ordering::Greater => invalid(),
+        Ordering::Equal => with_sign(f128::INFINITY, is_negative(x)),
+        Ordering::Less if is_zero(x) => x,
+        Ordering::Less => {
+            // atanh|x| = ln(1 + 2|x| / (1 - |x|)) / 2.
+            let a = Wide::from_f128(x).abs();
+            let result = ln_1p(a.scale(1) / (ONE - a)).scale(-1).to_f128();
+            with_sign(result, is_negative(x))
+        }
+    }
+}
+
+/// The bits of 2/pi after the point, enough for `reduce` to handle every finite `f128`.
+const TWO_OVER_PI: [u64; 265] = [
+    0xa2f9_836e_4e44_1529, 0xfc27_57d1_f534_ddc0, 0xdb62_9599_3c43_9041, 0xfe51_63ab_debb_c561,
+    0xb724_6e3a_424d_d2e0, 0x0649_2eea_09d1_921c, 0xfe1d_eb1c_b129_a73e, 0xe882_35f5_2ebb_4484,
+    0xe99c_7026_b45f_7e41, 0x3991_d639_8353_39f4, 0x9c84_5f8b_bdf9_283b, 0x1ff8_97ff_de05_980f,
+    0xef2f_118b_5a0a_6d1f, 0x6d36_7ecf_27cb_09b7, 0x4f46_3f66_9e5f_ea2d, 0x7527_bac7_ebe5_f17b,
+    0x3d07_39f7_8a52_92ea, 0x6bfb_5fb1_1f8d_5d08, 0x5603_3046_fc7b_6bab, 0xf0cf_bc20_9af4_361d,
+    0xa9e3_9161_5ee6_1b08, 0x6599_855f_14a0_6840, 0x8dff_d880_4d73_2731, 0x0606_1556_ca73_a8c9,
+    0x60e2_7bc0_8c6b_47c4, 0x19c3_67cd_dce8_092a, 0x8359_c476_8b96_1ca6, 0xddaf_44d1_5719_053e,
+    0xa5ff_0705_3f7e_33e8, 0x32c2_de4f_9832_7dbb, 0xc33d_26ef_6b1e_5ef8, 0x9f3a_1f35_caf2_7f1d,
+    0x87f1_2190_7c7c_246a, 0xfa6e_d577_2d30_433b, 0x15c6_14b5_9d19_c3c2, 0xc4ad_414d_2c5d_000c,
+    0x467d_862d_71e3_9ac6, 0x9b00_6233_7cd2_b497, 0xa7b4_d555_37f6_3ed7, 0x1810_a3fc_764d_2a9d,
+    0x64ab_d770_f87c_6357, 0xb07a_e715_1756_49c0, 0xd9d6_3b38_84a7_cb23, 0x2477_8ad6_2354_5ab9,
+    0x1f00_1b0a_f1df_ce19, 0xff31_9f6a_1e66_6157, 0x9947_fbac_d87f_7eb7, 0x6522_89e8_3260_bfe6,
+    0xcdc4_ef09_366c_d43f, 0x5dd7_de16_de3b_5892, 0x9bde_2822_d2e8_8628, 0x4d58_e232_cac6_16e3,
+    0x08cb_7de0_50c0_17a7, 0x1df3_5be0_1834_132e, 0x6212_8301_4883_5b8e, 0xf57f_b0ad_f2e9_1e43,
+    0x4a48_d367_10d8_ddaa, 0x425f_aece_616a_a428, 0x0ab4_99d3_f2a6_067f, 0x775c_83c2_a388_3c61,
+    0x7873_8a5a_8caf_bdd7, 0x6f63_a62d_cbbf_f4ef, 0x818d_67c1_2645_ca55, 0x36d9_cad2_a828_8d61,
+    0xc277_c912_1426_049b, 0x4612_c459_c444_c5c8, 0x91b2_4df3_1700_ad43, 0xd4e5_4929_10d5_fdfc,
+    0xbe00_cc94_1eee_ce70, 0xf53e_1380_f1ec_c3e7, 0xb328_f8c7_9405_933e, 0x71c1_b309_2ef3_450b,
+    0x9c12_887b_20ab_9fb5, 0x2ec2_9247_2f32_7b6d, 0x550c_90a7_721f_e76b, 0x96cb_314a_1679_e279,
+    0x4189_dff4_9794_e884, 0xe6e2_9731_996b_ed88, 0x365f_5f0e_fdbb_b49a, 0x486c_a467_4272_7132,
+    0x5d8d_b815_9f09_e5bc, 0x2531_8d39_74f7_1c05, 0x3001_0c0d_6808_4b58, 0xee2c_90aa_4702_e774,
+    0x24d6_bda6_7df7_7248, 0x6eef_169f_a694_8ef6, 0x91b4_5153_d1f2_0acf, 0x3398_207e_4bf5_6863,
+    0xb25f_3edd_035d_407f, 0x8985_2952_55c0_6437, 0x10d8_6d32_4832_754c, 0x5bd4_714e_6e54_45c1,
+    0x090b_69f5_2ad5_6614, 0x9d07_2750_045d_db3b, 0xb4c5_76ea_17f9_877d, 0x6b49_ba27_1d29_6996,
+    0xaccc_c654_14ad_6ae2, 0x9089_d988_5072_2cbe, 0xa404_9407_7770_30f3, 0x27fc_00a8_71ea_49c2,
+    0x663d_e064_83dd_9797, 0x3fa3_fd94_438c_860d, 0xde41_319d_3992_8c70, 0xdde7_b717_3bdf_082b,
+    0x3715_a080_5c93_805a, 0x9211_10d8_e80f_af80, 0x6c4b_ffdb_0f90_3876, 0x1859_15a5_62bb_cb61,
+    0xb989_c7bd_4010_04f2, 0xd227_7549_f6b6_ebbb, 0x22db_aa14_0a2f_2689, 0x7683_6433_3b09_1a94,
+    0x0eaa_3a51_c2a3_1dae, 0xedaf_1226_5c4d_c26d, 0x9c7a_2d97_56c0_833f, 0x03f6_f009_8c40_2b99,
+    0x316d_07b4_3915_200c, 0x5bc3_d8c4_92f5_4bad, 0xc6a5_ca4e_cd37_a736, 0xa9e6_9492_ab68_42dd,
+    0xde63_19ef_8c76_528b, 0x6837_dbfc_aba1_ae31, 0x15df_a1ae_00da_fb0c, 0x664d_64b7_05ed_3065,
+    0x29bf_5657_3aff_47b9, 0xf96a_f3be_75df_9328, 0x3080_abf6_8c66_15cb, 0x0406_22fa_1de4_d9a4,
+    0xb33d_8f1b_5709_cd36, 0xe942_4ea4_be13_b523, 0x331a_aaf0_a865_4fa5, 0xc1d2_0f3f_0bcd_785b,
+    0x76f9_2304_8b7b_7217, 0x8953_a6c6_e26e_6f00, 0xebef_584a_9bb7_dac4, 0xba66_aacf_cf76_1d02,
+    0xd12d_f1b1_c199_8c77, 0xadc3_da48_86a0_5df7, 0xf480_c62f_f0ac_9aec, 0xddbc_5c3f_6dde_d01f,
+    0xc790_b6db_2a3a_25a3, 0x9aaf_0093_53ad_0457, 0xb6b4_2d29_7e80_4ba7, 0x07da_0eaa_76a1_597b,
+    0x2a12_162d_b7dc_fde5, 0xfafe_db89_fdbe_896c, 0x76e4_fca9_0670_803e, 0x156e_85ff_87fd_073e,
+    0x2833_6761_8618_2aea, 0xbd4d_afe7_b36e_6d8f, 0x3967_955b_bf31_48d7, 0x8416_df30_432d_c735,
+    0x6125_ce70_c9b8_cb30, 0xfd6c_bfa2_00a4_e46c, 0x05a0_dd5a_476f_21d2, 0x1262_845c_b949_6170,
+    0xe056_6b01_5299_3755, 0x50b7_d51e_c4f1_335f, 0x6e13_e430_5da9_2e85, 0xc3b2_1d36_32a1_a4b7,
+    0x08d4_b1ea_21f7_16e4, 0x698f_77ff_2780_030c, 0x2d40_8da0_cd4f_99a5, 0x20d3_a2b3_0a5d_2f42,
+    0xf9b4_cbda_11d0_be7d, 0xc1db_9bbd_17ab_81a2, 0xca5c_6a08_1755_2e55, 0x0027_f014_7f86_07e1,
+    0x640b_148d_4196_debe, 0x872a_fdda_b625_6b34, 0x897b_fef3_059e_bfb9, 0x4f6a_68a8_2a4a_5ac4,
+    0x4fbc_f82d_985a_d795, 0xc7f4_8d4d_0da6_3a20, 0x5f57_a4b1_3f14_9538, 0x8001_20cc_86dd_71b6,
+    0xdec9_f560_bf11_654d, 0x6b07_01ac_b08c_d0c0, 0xb248_5551_0efb_1ec3, 0x7295_3b06_a335_40c0,
+    0x7bdc_06cc_45e0_fa29, 0x4ec8_cad6_41f3_e8de, 0x647c_d864_9b31_bed9, 0xc397_a4d4_5877_c5e3,
+    0x6913_daf0_3c3a_ba46, 0x1846_5f75_55f5_bdd2, 0xc692_6e5d_2eac_ed44, 0x0e42_3e1c_87c4_61e9,
+    0xfd29_f3d6_e7ca_7c22, 0x3591_6fc5_e008_8dd7, 0xffe2_6a6e_c6fd_b0c1, 0x0893_745d_7cb2_ad6b,
+    0x9d6e_cd7b_723e_6a11, 0xc6a9_cff7_df73_29ba, 0xc9b5_5100_b70d_b2e2, 0x24ba_7460_7de5_8ad8,
+    0x742c_150d_0c18_8194, 0x667e_1629_0176_7a9f, 0xbefd_fdef_4556_367e, 0xd913_d9ec_b9ba_8bfc,
+    0x97c4_27a8_31c3_6ef1, 0x36c5_9456_a8d8_b5a8, 0xb40e_cccf_2d89_1234, 0x576f_8956_2ce3_ce99,
+    0xb920_d6aa_5e6b_9c2a, 0x3ecc_5f11_4a0b_fdfb, 0xf4e1_6d3b_8e2c_86e2, 0x84d4_e9a9_b4fc_d1ee,
+    0xefc9_352e_6139_2f44, 0x2138_c8d9_1b0a_fc81, 0x6a4a_fbd8_1c2f_84b4, 0x538c_994e_cc22_54dc,
+    0x552a_d6c6_c096_190b, 0xb870_1a64_9569_605a, 0x26ee_523f_0f11_7f11, 0xb5f4_f5cb_fc2d_bc34,
+    0xeebc_34cc_5de8_605e, 0xdd9b_8e67_ef33_92b8, 0x17c9_9b58_61bc_57e1, 0xc683_5110_3ed8_4871,
+    0xdddd_1c2d_a118_af46, 0x2c21_d7f3_5998_7ad9, 0xc054_9efa_864f_fc06, 0x56ae_79e5_3622_8922,
+    0xad38_dc93_67aa_e855, 0x3826_829b_e7ca_a40d, 0x51b1_3399_0ed7_a948, 0x0569_f0b2_65a7_887f,
+    0x974c_8836_d1f9_b392, 0x214a_827b_21cf_98dc, 0x9f40_5547_dc3a_74e1, 0x42eb_67df_9dfe_5fd4,
+    0x5ea4_677b_7aac_baa2, 0xf655_2388_2b55_ba41, 0x086e_5986_2a21_8347, 0x39e6_e389_d49e_e540,
+    0xfb49_e956_ffca_0f1c, 0x8a59_c52b_fa94_c5c1, 0xd3cf_c50f_ae5a_db86, 0xc547_6243_853b_8621,
+    0x9479_2c87_6110_7b4c, 0x2a1a_2c80_12bf_4390, 0x2688_893c_78e4_c4a8, 0x7bdb_e5c2_3ac4_eaf4,
+    0x268a_67f7_bf92_0d2b, 0xa365_b193_3d0b_7cbd, 0xdc51_a463_dd27_dde1, 0x6919_949a_9529_a828,
+    0xce68_b4ed_0920_9f44, 0xca98_4e63_8270_237c, 0x7e32_b90f_8ef5_a7e7, 0x5614_08f1_212a_9db5,
+    0x4d7e_6f51_19a5_abf9, 0xb5d6_df82_61dd_9602, 0x3616_9f3a_c4a1_a283, 0x6ded_727a_8d39_a9b8,
+    0x825c_326b_5b27_46ed, 0x3400_7700_d255_f4fc, 0x4d59_0180_71e0_e13f, 0x89b2_95f3_64a8_f1ae,
+    0xa74b_38fc_4cea_b2bb, 0x4727_0bab_c3a7_34ba, 0x6052_dd34_f856_3aeb, 0x7e8a_31bb_3658_95b7,
+    0x47f7_a994_c3aa_d392,
+];
@@ -0,0 +1,85 @@
+//! The IEEE 754 totalOrder predicate, and a wrapper that orders `f128` by it.
+
+use f128_t::f128;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+impl f128 {
+    /// Compares `self` and `other` by the totalOrder predicate of IEEE 754: from smallest to
+    /// largest, negative NaNs, negative infinity, negative numbers, `-0`, `+0`, positive numbers,
+    /// positive infinity and positive NaNs. NaNs are ordered by their payload, signalling below
+    /// quiet ones on the positive side and above them on the negative side.
+    ///
+    /// This only looks at the bits, so it is `Equal` exactly when they are, and it never raises
+    /// floating-point exceptions.
+    #[inline]
+    pub fn total_cmp(&self, other: &f128) -> Ordering {
+        total_key(*self).cmp(&total_key(*other))
+    }
+}
+
+/// The bits of `x` as an integer that orders like `x` does in totalOrder: negative values have
+/// their magnitude bits flipped so that larger magnitudes compare smaller.
+#[inline]
+fn total_key(x: f128) -> i128 {
+    let bits = x.to_bits() as i128;
+    bits ^ (((bits >> 127) as u128) >> 1) as i128
+}
+
+/// An `f128` ordered by `f128::total_cmp`, so that it implements `Ord`, `Eq` and `Hash` and can be
+/// sorted, binary searched, and used as a key of a `BTreeMap` or `HashMap`.
+///
+/// Two values are equal only if their bits are: `-0` and `+0` differ, as do NaNs with different
+/// payloads, while each NaN equals itself.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TotalF128(pub f128);
+
+impl PartialEq for TotalF128 {
+    #[inline]
+    fn eq(&self, other: &TotalF128) -> bool {
+        self.0.to_bits() == other.0.to_bits()
+    }
+}
+
+impl Eq for TotalF128 {}
+
+impl PartialOrd for TotalF128 {
+    #[inline]
+    fn partial_cmp(&self, other: &TotalF128) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TotalF128 {
+    #[inline]
+    fn cmp(&self, other: &TotalF128) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Hash for TotalF128 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u128(self.0.to_bits())
+    }
+}
+
+impl fmt::Display for TotalF128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<f128> for TotalF128 {
+    #[inline]
+    fn from(x: f128) -> TotalF128 {
+        TotalF128(x)
+    }
+}
+
+impl From<TotalF128> for f128 {
+    #[inline]
+    fn from(x: TotalF128) -> f128 {
+        x.0
+    }
+}
@@ -0,0 +1,100 @@
+//! Stepping between adjacent `f128` values and measuring distances in units in the last place.
+//! Everything works on the bit pattern, where adjacent values of one sign have adjacent encodings.
+
+use f128_t::f128;
+use num_traits::Float;
+use soft;
+
+/// `-0` and `+0` without their sign.
+const MAGNITUDE_MASK: u128 = !(1 << 127);
+
+impl f128 {
+    /// The smallest value greater than `self`: the smallest positive subnormal for either zero,
+    /// `-0` for the largest negative subnormal, and `MIN` for negative infinity. Positive infinity
+    /// stays where it is and NaN stays NaN. This is IEEE 754's `nextUp`.
+    pub fn next_up(self) -> f128 {
+        if self.is_nan() {
+            return soft::nan_result(self, self);
+        }
+        let bits = self.to_bits();
+        if bits & MAGNITUDE_MASK == 0 {
+            f128::MIN_POSITIVE_SUBNORMAL
+        } else if self == f128::INFINITY {
+            self
+        } else if self.is_sign_bit_set() {
+            f128::from_bits(bits - 1)
+        } else {
+            f128::from_bits(bits + 1)
+        }
+    }
+
+    /// The largest value less than `self`, mirroring `next_up`: `-next_up(-self)`. This is
+    /// IEEE 754's `nextDown`.
+    pub fn next_down(self) -> f128 {
+        -(-self).next_up()
+    }
+
+    /// The value next to `self` in the direction of `toward`, or `toward` itself if they are equal,
+    /// so that stepping from `+0` toward `-0` gives `-0`. NaN if either is NaN. Like C's
+    /// `nextafterq`, but without raising overflow or underflow.
+    pub fn next_after(self, toward: f128) -> f128 {
+        if self.is_nan() || toward.is_nan() {
+            return soft::nan_result(self, toward);
+        }
+        if self == toward {
+            toward
+        } else if self < toward {
+            self.next_up()
+        } else {
+            self.next_down()
+        }
+    }
+
+    /// The unit in the last place of `self`: the distance from `|self|` to the next value away
+    /// from zero, as though the exponent range had no upper limit. That is the smallest positive
+    /// subnormal for zeros and subnormals, and `2^(e - 112)` for normal values in `[2^e, 2^(e+1))`.
+    /// Always positive, and NaN for infinities and NaNs.
+    pub fn ulp(self) -> f128 {
+        if !self.is_finite() {
+            return f128::NAN;
+        }
+        match self.exp_bits() {
+            0 => f128::MIN_POSITIVE_SUBNORMAL,
+            // Biased exponents up to 112 give a subnormal ulp.
+            exp if exp <= 112 => f128::from_bits(1 << (exp - 1)),
+            exp => f128::from_bits(((exp - 112) as u128) << 112),
+        }
+    }
+
+    /// How many steps of `next_up` lead from the smaller of `self` and `other` to the larger, with
+    /// `-0` and `+0` counting as one value. 0 if they are equal, 1 if they are adjacent, and
+    /// `ulps_between(-x, x)` is twice `ulps_between(0, x)`.
+    ///
+    /// # Panics
+    ///
+    /// If either value is NaN.
+    pub fn ulps_between(self, other: f128) -> u128 {
+        assert!(
+            !self.is_nan() && !other.is_nan(),
+            "ulps_between: NaN has no place among the f128 values"
+        );
+        let (low, high) = if ordinal(self) < ordinal(other) {
+            (ordinal(self), ordinal(other))
+        } else {
+            (ordinal(other), ordinal(self))
+        };
+        // The difference between the infinities doesn't fit in an `i128`, but does in a `u128`.
+        (high as u128).wrapping_sub(low as u128)
+    }
+}
+
+/// The position of `x` among the non-NaN values, counted from zero, so that adjacent values have
+/// adjacent positions.
+fn ordinal(x: f128) -> i128 {
+    let magnitude = (x.to_bits() & MAGNITUDE_MASK) as i128;
+    if x.is_sign_bit_set() {
+        -magnitude
+    } else {
+        magnitude
+    }
+}